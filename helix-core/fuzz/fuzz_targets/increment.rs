@@ -0,0 +1,13 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Exercises `helix_core::increment`'s entry points with arbitrary selected
+// text: per the non-panicking invariant documented at the top of
+// `helix-core/src/increment/integer.rs`, these must return `None` on input
+// they can't make sense of, never panic.
+fuzz_target!(|data: (&str, i64)| {
+    let (selected_text, amount) = data;
+    let _ = helix_core::increment::integer(selected_text, amount);
+    let _ = helix_core::increment::date_time(selected_text, amount);
+});
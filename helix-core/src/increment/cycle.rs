@@ -0,0 +1,97 @@
+//! Cycles a word through a small ordered list instead of doing arithmetic,
+//! e.g. toggling `debug`/`info`/`warn`/`error` or `TODO`/`FIXME`/`DONE`.
+
+/// The case pattern of a word, so [`cycle`] can carry it over to the word it
+/// lands on instead of always using `words`' own casing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Upper,
+    Lower,
+    Title,
+    /// Mixed case, non-alphabetic, or otherwise not one of the above: keep
+    /// the matched word's casing from `words` verbatim.
+    AsWritten,
+}
+
+fn detect_case(word: &str) -> Case {
+    let has_alphabetic = word.chars().any(|c| c.is_alphabetic());
+    if has_alphabetic && word.chars().all(|c| !c.is_alphabetic() || c.is_uppercase()) {
+        Case::Upper
+    } else if has_alphabetic && word.chars().all(|c| !c.is_alphabetic() || c.is_lowercase()) {
+        Case::Lower
+    } else {
+        let mut chars = word.chars();
+        match chars.next() {
+            Some(first)
+                if first.is_uppercase() && chars.all(|c| !c.is_alphabetic() || c.is_lowercase()) =>
+            {
+                Case::Title
+            }
+            _ => Case::AsWritten,
+        }
+    }
+}
+
+fn apply_case(word: &str, case: Case) -> String {
+    match case {
+        Case::Upper => word.to_uppercase(),
+        Case::Lower => word.to_lowercase(),
+        Case::Title => {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => {
+                    first.to_uppercase().collect::<String>() + &chars.as_str().to_lowercase()
+                }
+                None => String::new(),
+            }
+        }
+        Case::AsWritten => word.to_string(),
+    }
+}
+
+/// Moves `amount` steps through `words` (wrapping both ways), starting from
+/// wherever `text` matches one of them case-insensitively, and re-applies
+/// `text`'s own case pattern (all upper, all lower, or Title) to the result.
+/// Generalizes a boolean toggle to any small ordered set, e.g.
+/// `debug`/`info`/`warn`/`error` or `TODO`/`FIXME`/`DONE`. Returns `None` if
+/// `text` doesn't match any entry in `words`.
+pub fn cycle(text: &str, amount: i64, words: &[&str]) -> Option<String> {
+    if words.is_empty() {
+        return None;
+    }
+
+    let index = words.iter().position(|word| word.eq_ignore_ascii_case(text))?;
+    let len = words.len() as i64;
+    let new_index = (index as i64 + amount).rem_euclid(len) as usize;
+
+    Some(apply_case(words[new_index], detect_case(text)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle_steps_and_wraps() {
+        let words = ["debug", "info", "warn", "error"];
+        assert_eq!(cycle("debug", 1, &words).unwrap(), "info");
+        assert_eq!(cycle("error", 1, &words).unwrap(), "debug");
+        assert_eq!(cycle("debug", -1, &words).unwrap(), "error");
+        assert_eq!(cycle("info", 6, &words).unwrap(), "error");
+    }
+
+    #[test]
+    fn test_cycle_preserves_case_pattern() {
+        let words = ["todo", "fixme", "done"];
+        assert_eq!(cycle("TODO", 1, &words).unwrap(), "FIXME");
+        assert_eq!(cycle("Todo", 1, &words).unwrap(), "Fixme");
+        assert_eq!(cycle("todo", 1, &words).unwrap(), "fixme");
+    }
+
+    #[test]
+    fn test_cycle_rejects_unknown_words() {
+        let words = ["debug", "info", "warn", "error"];
+        assert_eq!(cycle("trace", 1, &words), None);
+        assert_eq!(cycle("debug", 1, &[]), None);
+    }
+}
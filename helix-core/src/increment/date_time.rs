@@ -1,8 +1,11 @@
-use chrono::{Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::fmt::Write;
 
+use super::locale::{locale_for, Locale, LOCALES};
+pub(crate) use super::locale::detect_locale;
+
 /// Increment a Date or DateTime
 ///
 /// If just a Date is selected the day will be incremented.
@@ -50,6 +53,261 @@ pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
     })
 }
 
+/// The date plus enough style information (long vs. short month/weekday
+/// name) to render the incremented result back the way it was written.
+struct NamedMatch {
+    date: NaiveDate,
+    month_long: bool,
+    weekday_long: Option<bool>,
+}
+
+/// Builds a case-insensitive regex alternation of `names`, longest first so
+/// e.g. Spanish `"marzo"` (March, long) isn't shadowed by the short
+/// `"mar"`, which happens to be a prefix of it.
+fn name_alt(names: impl Iterator<Item = &'static str>) -> String {
+    let mut names: Vec<&str> = names.collect();
+    names.sort_by_key(|name| std::cmp::Reverse(name.len()));
+    names.iter().map(|name| regex::escape(name)).collect::<Vec<_>>().join("|")
+}
+
+fn month_alt(locale: &Locale) -> String {
+    name_alt(locale.months_long.into_iter().chain(locale.months_short))
+}
+
+fn weekday_alt(locale: &Locale) -> String {
+    name_alt(locale.weekdays_long.into_iter().chain(locale.weekdays_short))
+}
+
+fn month_index(locale: &Locale, text: &str) -> Option<(u32, bool)> {
+    if let Some(i) = locale.months_long.iter().position(|name| name.eq_ignore_ascii_case(text)) {
+        return Some((i as u32, true));
+    }
+    let i = locale.months_short.iter().position(|name| name.eq_ignore_ascii_case(text))?;
+    Some((i as u32, false))
+}
+
+fn weekday_is_long(locale: &Locale, text: &str) -> Option<bool> {
+    if locale.weekdays_long.iter().any(|name| name.eq_ignore_ascii_case(text)) {
+        return Some(true);
+    }
+    locale
+        .weekdays_short
+        .iter()
+        .any(|name| name.eq_ignore_ascii_case(text))
+        .then_some(false)
+}
+
+fn parse_weekday_month_day_year(text: &str, locale: &Locale) -> Option<NamedMatch> {
+    let pattern = format!(r"(?i)^({})\s+({})\s+([0-3]\d)\s+(\d{{4}})$", weekday_alt(locale), month_alt(locale));
+    let caps = Regex::new(&pattern).ok()?.captures(text)?;
+    let weekday_long = weekday_is_long(locale, &caps[1])?;
+    let (month, month_long) = month_index(locale, &caps[2])?;
+    let date = NaiveDate::from_ymd_opt(caps[4].parse().ok()?, month + 1, caps[3].parse().ok()?)?;
+    Some(NamedMatch { date, month_long, weekday_long: Some(weekday_long) })
+}
+
+fn render_weekday_month_day_year(locale: &Locale, date: NaiveDate, m: &NamedMatch) -> String {
+    let weekday_idx = date.weekday().num_days_from_monday() as usize;
+    let weekday = if m.weekday_long.unwrap_or(false) {
+        locale.weekdays_long[weekday_idx]
+    } else {
+        locale.weekdays_short[weekday_idx]
+    };
+    let month = if m.month_long {
+        locale.months_long[date.month0() as usize]
+    } else {
+        locale.months_short[date.month0() as usize]
+    };
+    format!("{weekday} {month} {:02} {}", date.day(), date.year())
+}
+
+fn parse_day_month_year_dashed(text: &str, locale: &Locale) -> Option<NamedMatch> {
+    let pattern = format!(r"(?i)^([0-3]\d)-({})-(\d{{4}})$", month_alt(locale));
+    let caps = Regex::new(&pattern).ok()?.captures(text)?;
+    let (month, month_long) = month_index(locale, &caps[2])?;
+    let date = NaiveDate::from_ymd_opt(caps[3].parse().ok()?, month + 1, caps[1].parse().ok()?)?;
+    Some(NamedMatch { date, month_long, weekday_long: None })
+}
+
+fn render_day_month_year_dashed(locale: &Locale, date: NaiveDate, m: &NamedMatch) -> String {
+    let month = if m.month_long {
+        locale.months_long[date.month0() as usize]
+    } else {
+        locale.months_short[date.month0() as usize]
+    };
+    format!("{:02}-{}-{}", date.day(), month, date.year())
+}
+
+fn parse_year_month_day(text: &str, locale: &Locale) -> Option<NamedMatch> {
+    let pattern = format!(r"(?i)^(\d{{4}}) ({}) ([0-3]\d)$", month_alt(locale));
+    let caps = Regex::new(&pattern).ok()?.captures(text)?;
+    let (month, month_long) = month_index(locale, &caps[2])?;
+    let date = NaiveDate::from_ymd_opt(caps[1].parse().ok()?, month + 1, caps[3].parse().ok()?)?;
+    Some(NamedMatch { date, month_long, weekday_long: None })
+}
+
+fn render_year_month_day(locale: &Locale, date: NaiveDate, m: &NamedMatch) -> String {
+    let month = if m.month_long {
+        locale.months_long[date.month0() as usize]
+    } else {
+        locale.months_short[date.month0() as usize]
+    };
+    format!("{} {} {:02}", date.year(), month, date.day())
+}
+
+fn parse_month_day_comma_year(text: &str, locale: &Locale) -> Option<NamedMatch> {
+    let pattern = format!(r"(?i)^({}) ([0-3]\d), (\d{{4}})$", month_alt(locale));
+    let caps = Regex::new(&pattern).ok()?.captures(text)?;
+    let (month, month_long) = month_index(locale, &caps[1])?;
+    let date = NaiveDate::from_ymd_opt(caps[3].parse().ok()?, month + 1, caps[2].parse().ok()?)?;
+    Some(NamedMatch { date, month_long, weekday_long: None })
+}
+
+fn render_month_day_comma_year(locale: &Locale, date: NaiveDate, m: &NamedMatch) -> String {
+    let month = if m.month_long {
+        locale.months_long[date.month0() as usize]
+    } else {
+        locale.months_short[date.month0() as usize]
+    };
+    format!("{} {:02}, {}", month, date.day(), date.year())
+}
+
+type NamedParse = fn(&str, &Locale) -> Option<NamedMatch>;
+type NamedRender = fn(&Locale, NaiveDate, &NamedMatch) -> String;
+
+// One entry per written-month-name format in `FORMATS` below (`%a %b %d
+// %Y`, `%d-%b-%Y`, `%Y %b %d`, `%b %d, %Y`): `chrono`'s own `%a`/`%b`
+// parsing and formatting only understands English names, so these
+// formats get their own locale-aware regex + renderer instead of reusing
+// `Format`/`DateField`.
+const NAMED_FORMATS: &[(NamedParse, NamedRender)] = &[
+    (parse_weekday_month_day_year, render_weekday_month_day_year),
+    (parse_day_month_year_dashed, render_day_month_year_dashed),
+    (parse_year_month_day, render_year_month_day),
+    (parse_month_day_comma_year, render_month_day_comma_year),
+];
+
+/// Like [`increment`], but for the written-month-name formats (`Wed Nov 24
+/// 2021`, `24-Nov-2021`, `2021 Nov 24`, `Nov 24, 2021`), rendered using
+/// `locale`'s month/weekday names (`"de"`, `"fr"`, `"es"`, ...) instead of
+/// English. An unrecognized locale tag falls back to English. Pass
+/// [`detect_locale`]'s result to follow the environment instead of a fixed
+/// locale.
+///
+/// The input itself is recognized in *any* supported locale, not just the
+/// target one - a selection doesn't carry its own locale tag with it, so
+/// this is the only way to accept e.g. a German date while rendering it
+/// back out in French.
+pub fn increment_locale(selected_text: &str, amount: i64, locale: &str) -> Option<String> {
+    if selected_text.is_empty() {
+        return None;
+    }
+    let target = locale_for(locale);
+    NAMED_FORMATS.iter().find_map(|(parse, render)| {
+        let m = LOCALES.iter().find_map(|source| parse(selected_text, source))?;
+        let new_date = m.date.checked_add_signed(Duration::days(amount))?;
+        Some(render(target, new_date, &m))
+    })
+}
+
+/// A field [`increment_two_digit_year`]'s format descriptor can name.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum TwoDigitField {
+    Year,
+    Month,
+    Day,
+}
+
+/// Parses a small `"MM/DD/YY"`-style format descriptor into field order and
+/// separator. Unlike the `%`-style [`Format`] this module otherwise uses,
+/// this only ever describes exactly a year, a month, and a day, each
+/// exactly two digits wide, in whatever order and with whatever single
+/// separator character the caller's dates actually use.
+fn parse_two_digit_year_format(format: &str) -> Option<(Vec<TwoDigitField>, char)> {
+    let sep = format.chars().find(|c| !c.is_ascii_alphabetic())?;
+    let fields: Vec<TwoDigitField> = format
+        .split(sep)
+        .map(|token| match token {
+            "YY" => Some(TwoDigitField::Year),
+            "MM" => Some(TwoDigitField::Month),
+            "DD" => Some(TwoDigitField::Day),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let has_all_three = fields.contains(&TwoDigitField::Year)
+        && fields.contains(&TwoDigitField::Month)
+        && fields.contains(&TwoDigitField::Day);
+    (fields.len() == 3 && has_all_three).then_some((fields, sep))
+}
+
+/// Maps a two-digit year to a full year using `pivot`: `yy < pivot` reads as
+/// `2000 + yy`, `yy >= pivot` as `1900 + yy` - the same century-pivot
+/// convention spreadsheets and COBOL-style date libraries use for bare `YY`
+/// input, e.g. a pivot of `50` reads `"49"` as `2049` but `"50"` as `1950`.
+fn year_from_two_digit(yy: u32, pivot: u32) -> i32 {
+    if yy < pivot {
+        2000 + yy as i32
+    } else {
+        1900 + yy as i32
+    }
+}
+
+/// Increments a two-digit-year date like `01/02/24` by `amount` days,
+/// carrying correctly through month/day and century-rolling `YY` through
+/// `pivot` (see [`year_from_two_digit`]) rather than chrono's own fixed
+/// `%y` pivot. `format` names the field order and separator, e.g.
+/// `"MM/DD/YY"` or `"DD-MM-YY"` (see [`parse_two_digit_year_format`]).
+/// Returns `None` if `selected_text` doesn't match `format`, or if either
+/// the parsed or the resulting date isn't a real calendar date.
+pub fn increment_two_digit_year(
+    selected_text: &str,
+    amount: i64,
+    format: &str,
+    pivot: u32,
+) -> Option<String> {
+    let (fields, sep) = parse_two_digit_year_format(format)?;
+    let parts: Vec<&str> = selected_text.split(sep).collect();
+    if parts.len() != 3
+        || parts
+            .iter()
+            .any(|p| p.len() != 2 || !p.bytes().all(|b| b.is_ascii_digit()))
+    {
+        return None;
+    }
+
+    let mut year = None;
+    let mut month = None;
+    let mut day = None;
+    for (field, part) in fields.iter().zip(&parts) {
+        let value: u32 = part.parse().ok()?;
+        match field {
+            TwoDigitField::Year => year = Some(value),
+            TwoDigitField::Month => month = Some(value),
+            TwoDigitField::Day => day = Some(value),
+        }
+    }
+    let (yy, month, day) = (year?, month?, day?);
+
+    let full_year = year_from_two_digit(yy, pivot);
+    let date = NaiveDate::from_ymd_opt(full_year, month, day)?;
+    let new_date = date.checked_add_signed(Duration::days(amount))?;
+    let new_yy = new_date.year().rem_euclid(100) as u32;
+
+    let rendered: Vec<String> = fields
+        .iter()
+        .map(|field| match field {
+            TwoDigitField::Year => format!("{new_yy:02}"),
+            TwoDigitField::Month => format!("{:02}", new_date.month()),
+            TwoDigitField::Day => format!("{:02}", new_date.day()),
+        })
+        .collect();
+
+    Some(rendered.join(&sep.to_string()))
+}
+
+// Compiled once and reused across calls (important for multi-cursor
+// increments, which call `increment` once per selection).
 static FORMATS: Lazy<Vec<Format>> = Lazy::new(|| {
     vec![
         Format::new("%Y-%m-%d %H:%M:%S"), // 2021-11-24 07:12:23
@@ -317,4 +575,82 @@ fn test_invalid_date_times() {
             assert_eq!(increment(invalid, 1), None)
         }
     }
+
+    #[test]
+    fn test_increment_locale_renders_the_requested_locale() {
+        let tests = [
+            ("Wed Nov 24 2021", 1, "de", "Do Nov 25 2021"),
+            ("24-Nov-2021", 1, "fr", "25-nov.-2021"),
+            ("2021 Nov 24", 1, "es", "2021 nov 25"),
+            ("Nov 24, 2021", 1, "en", "Nov 25, 2021"),
+        ];
+
+        for (original, amount, locale, expected) in tests {
+            assert_eq!(increment_locale(original, amount, locale).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_locale_parses_names_in_any_supported_locale() {
+        // The input is already German; incrementing still works even
+        // though the caller asked to render as German, i.e. round-trips.
+        assert_eq!(
+            increment_locale("Donnerstag Nov 25 2021", 1, "de").unwrap(),
+            "Freitag Nov 26 2021"
+        );
+    }
+
+    #[test]
+    fn test_increment_locale_falls_back_to_english_for_unknown_locales() {
+        assert_eq!(
+            increment_locale("Nov 24, 2021", 1, "xx").unwrap(),
+            "Nov 25, 2021"
+        );
+    }
+
+    #[test]
+    fn test_increment_locale_rejects_non_matching_text() {
+        assert_eq!(increment_locale("not a date", 1, "de"), None);
+    }
+
+    #[test]
+    fn test_increment_two_digit_year_rolls_across_the_century_boundary() {
+        // 1999-12-31 (pivot 50 reads "99" as 1999) plus a day rolls into
+        // the next century, and the rendered "YY" rolls with it.
+        assert_eq!(
+            increment_two_digit_year("12/31/99", 1, "MM/DD/YY", 50).unwrap(),
+            "01/01/00"
+        );
+    }
+
+    #[test]
+    fn test_increment_two_digit_year_pivot_boundary() {
+        // Just below the pivot reads as 2000s...
+        assert_eq!(
+            increment_two_digit_year("01/01/49", 0, "MM/DD/YY", 50).unwrap(),
+            "01/01/49"
+        );
+        // ...right at the pivot reads as 1900s instead.
+        assert_eq!(
+            increment_two_digit_year("01/01/50", 0, "MM/DD/YY", 50).unwrap(),
+            "01/01/50"
+        );
+    }
+
+    #[test]
+    fn test_increment_two_digit_year_field_order_and_separator_preserved() {
+        assert_eq!(
+            increment_two_digit_year("24-11-30", 1, "YY-MM-DD", 50).unwrap(),
+            "24-12-01"
+        );
+    }
+
+    #[test]
+    fn test_increment_two_digit_year_rejects_mismatched_text() {
+        assert_eq!(
+            increment_two_digit_year("2024/11/30", 1, "MM/DD/YY", 50),
+            None
+        );
+        assert_eq!(increment_two_digit_year("13/40/99", 1, "MM/DD/YY", 50), None);
+    }
 }
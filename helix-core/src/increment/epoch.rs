@@ -0,0 +1,85 @@
+/// Options controlling how a bare integer is treated as a Unix timestamp by
+/// [increment]. A bare integer is ambiguous (is it seconds? milliseconds? a
+/// plain number?), so timestamp handling is only ever used when the caller
+/// explicitly invokes a timestamp-increment action with these options -
+/// never auto-detected the way the other `increment` modules are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EpochOptions {
+    /// Treat a 13-digit value as milliseconds instead of seconds.
+    pub detect_millis_by_width: bool,
+    /// Clamp the result to the 32-bit Unix time range (`[0, u32::MAX]`
+    /// seconds, or the equivalent in milliseconds) instead of only
+    /// saturating at zero.
+    pub saturate_at_32_bit: bool,
+}
+
+/// Increments a bare Unix epoch timestamp by `amount` seconds. Returns
+/// `None` for anything that isn't a plain non-negative integer literal
+/// (no `0x`/separators/sign - those are handled by [`super::integer`]).
+pub fn increment(selected_text: &str, amount: i64, opts: EpochOptions) -> Option<String> {
+    if selected_text.is_empty() || !selected_text.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+
+    let value: i64 = selected_text.parse().ok()?;
+    let is_millis = opts.detect_millis_by_width && selected_text.len() == 13;
+
+    let delta = if is_millis {
+        amount.saturating_mul(1000)
+    } else {
+        amount
+    };
+
+    let mut new_value = value.saturating_add(delta).max(0);
+
+    if opts.saturate_at_32_bit {
+        let max = i64::from(u32::MAX) * if is_millis { 1000 } else { 1 };
+        new_value = new_value.min(max);
+    }
+
+    Some(new_value.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_epoch_seconds() {
+        let opts = EpochOptions::default();
+        assert_eq!(increment("1700000000", 1, opts).unwrap(), "1700000001");
+        assert_eq!(increment("0", -1, opts).unwrap(), "0");
+    }
+
+    #[test]
+    fn test_increment_epoch_millis_detected_by_width() {
+        let opts = EpochOptions {
+            detect_millis_by_width: true,
+            ..EpochOptions::default()
+        };
+        // 13-digit value is treated as milliseconds, so a 1-second amount
+        // becomes a 1000ms delta.
+        assert_eq!(increment("1700000000000", 1, opts).unwrap(), "1700000001000");
+        // A 10-digit value is still seconds.
+        assert_eq!(increment("1700000000", 1, opts).unwrap(), "1700000001");
+    }
+
+    #[test]
+    fn test_increment_epoch_saturates_at_32_bit() {
+        let opts = EpochOptions {
+            saturate_at_32_bit: true,
+            ..EpochOptions::default()
+        };
+        assert_eq!(
+            increment(&u32::MAX.to_string(), 1, opts).unwrap(),
+            u32::MAX.to_string()
+        );
+    }
+
+    #[test]
+    fn test_increment_epoch_rejects_non_digits() {
+        assert_eq!(increment("-1", 1, EpochOptions::default()), None);
+        assert_eq!(increment("0x10", 1, EpochOptions::default()), None);
+        assert_eq!(increment("", 1, EpochOptions::default()), None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Increments a single hex escape sequence - `\xHH` (exactly two hex
+//! digits) or `\u{H..HHHHHH}` (1-6 hex digits, a Unicode code point) - by
+//! shifting its value, preserving the escape syntax and the digit case.
+//! `\xHH` wraps at the 8-bit boundary (`\xff` + 1 -> `\x00`); `\u{...}`
+//! clamps at the valid code point range instead of wrapping, stepping over
+//! the surrogate range rather than landing inside it, since neither end
+//! has a meaningful "next" value the way an 8-bit byte's wraparound does.
+
+const MAX_CODEPOINT: u32 = 0x10FFFF;
+const SURROGATE_START: u32 = 0xD800;
+const SURROGATE_END: u32 = 0xDFFF;
+
+/// Increments `\xHH` or `\u{...}` in `selected_text` by `amount`. Returns
+/// `None` for anything else, including a malformed escape (wrong digit
+/// count or a non-hex-digit character).
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    if let Some(hex) = selected_text.strip_prefix("\\x") {
+        return increment_byte_escape(hex, amount);
+    }
+    if let Some(hex) = selected_text
+        .strip_prefix("\\u{")
+        .and_then(|rest| rest.strip_suffix('}'))
+    {
+        return increment_unicode_escape(hex, amount);
+    }
+    None
+}
+
+/// Renders `value` using whichever case (upper/lower) dominates `hex`'s own
+/// digits, ties going to lowercase - same convention
+/// [`super::integer::rotate`] uses for a fixed-width hex literal with no
+/// case signal of its own. Shared with [`super::percent_escape`], which
+/// faces the same "preserve the input's digit case" requirement for its own
+/// 2-digit hex byte.
+pub(super) fn render_hex(hex: &str, value: u32, width: usize) -> String {
+    let (lower_count, upper_count): (usize, usize) = hex.chars().fold((0, 0), |(lower, upper), c| {
+        (
+            lower + c.is_ascii_lowercase() as usize,
+            upper + c.is_ascii_uppercase() as usize,
+        )
+    });
+    if upper_count > lower_count {
+        format!("{:01$X}", value, width)
+    } else {
+        format!("{:01$x}", value, width)
+    }
+}
+
+fn increment_byte_escape(hex: &str, amount: i64) -> Option<String> {
+    if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u8::from_str_radix(hex, 16).ok()?;
+    let new_value = (value as i64 + amount).rem_euclid(256) as u32;
+    Some(format!("\\x{}", render_hex(hex, new_value, 2)))
+}
+
+fn increment_unicode_escape(hex: &str, amount: i64) -> Option<String> {
+    if hex.is_empty() || hex.len() > 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let value = u32::from_str_radix(hex, 16).ok()?;
+    let mut new_value = (value as i64 + amount).clamp(0, MAX_CODEPOINT as i64) as u32;
+
+    if (SURROGATE_START..=SURROGATE_END).contains(&new_value) {
+        new_value = if amount >= 0 {
+            SURROGATE_END + 1
+        } else {
+            SURROGATE_START - 1
+        };
+    }
+
+    Some(format!("\\u{{{}}}", render_hex(hex, new_value, hex.len())))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_byte_escape() {
+        assert_eq!(increment("\\x1b", 1).unwrap(), "\\x1c");
+        assert_eq!(increment("\\xFF", 1).unwrap(), "\\x00");
+        assert_eq!(increment("\\x00", -1).unwrap(), "\\xff");
+        // Case follows the input's own digits.
+        assert_eq!(increment("\\x0A", 1).unwrap(), "\\x0B");
+        assert_eq!(increment("\\x0a", 1).unwrap(), "\\x0b");
+    }
+
+    #[test]
+    fn test_increment_unicode_escape() {
+        assert_eq!(increment("\\u{1F600}", 1).unwrap(), "\\u{1F601}");
+        // Clamped at the top of the valid range, not wrapped.
+        assert_eq!(increment("\\u{10FFFF}", 1).unwrap(), "\\u{10FFFF}");
+        // Clamped at the bottom.
+        assert_eq!(increment("\\u{0}", -1).unwrap(), "\\u{0}");
+        // Surrogate range is stepped over, not landed in.
+        assert_eq!(increment("\\u{D7FF}", 1).unwrap(), "\\u{E000}");
+        assert_eq!(increment("\\u{E000}", -1).unwrap(), "\\u{D7FF}");
+    }
+
+    #[test]
+    fn test_increment_rejects_malformed_escapes() {
+        assert_eq!(increment("\\x1", 1), None);
+        assert_eq!(increment("\\xzz", 1), None);
+        assert_eq!(increment("\\u{}", 1), None);
+        assert_eq!(increment("\\u{GG}", 1), None);
+        assert_eq!(increment("\\u{1234567}", 1), None);
+        assert_eq!(increment("1b", 1), None);
+    }
+}
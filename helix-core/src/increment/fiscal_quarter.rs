@@ -0,0 +1,51 @@
+/// Increments a fiscal-quarter literal like `FY24Q3`, cycling `Q1`-`Q4` and
+/// carrying into the two-digit fiscal year. The fiscal year wraps like a
+/// two-digit clock (`FY99` -> `FY00`) rather than erroring, since `FYxx` is
+/// already a truncated representation with no real upper bound to enforce.
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    let rest = selected_text.strip_prefix("FY")?;
+    let (year_str, quarter_str) = rest.split_once('Q')?;
+    if year_str.len() != 2 {
+        return None;
+    }
+
+    let year: i64 = year_str.parse().ok()?;
+    let quarter: i64 = quarter_str.parse().ok()?;
+    if !(1..=4).contains(&quarter) {
+        return None;
+    }
+
+    let total = year * 4 + (quarter - 1) + amount;
+    let new_year = total.div_euclid(4).rem_euclid(100);
+    let new_quarter = total.rem_euclid(4) + 1;
+
+    Some(format!("FY{new_year:02}Q{new_quarter}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_fiscal_quarter() {
+        let tests = [
+            ("FY24Q1", 1, "FY24Q2"),
+            ("FY24Q3", 1, "FY24Q4"),
+            ("FY24Q4", 1, "FY25Q1"),
+            ("FY25Q1", -1, "FY24Q4"),
+            ("FY99Q4", 1, "FY00Q1"),
+            ("FY00Q1", -1, "FY99Q4"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_fiscal_quarter_rejects_invalid_input() {
+        assert_eq!(increment("FY24Q5", 1), None);
+        assert_eq!(increment("FY24Q0", 1), None);
+        assert_eq!(increment("Q3", 1), None);
+    }
+}
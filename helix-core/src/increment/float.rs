@@ -0,0 +1,342 @@
+//! Increments a plain decimal literal like `3.14`, `5.`, or `.5` by
+//! shifting its last fractional digit - the decimal analogue of
+//! [`crate::increment::integer`]'s bare-integer arithmetic. Handles the
+//! bare trailing-dot (`5.`) and leading-dot (`.5`) shorthands code commonly
+//! writes a float literal in, not just the full `5.0` form.
+
+use super::integer::active_separator;
+
+/// Controls how [`increment_with_precision`] (and
+/// [`super::scientific::increment_with_precision`]) derive a result's
+/// fractional digits from the incremented value, instead of always
+/// preserving the input's own decimal-place count.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    /// Keep exactly `n` digits after the decimal point, regardless of the
+    /// input's own precision.
+    DecimalPlaces(u32),
+    /// Keep exactly `n` significant (non-leading-zero) digits, re-deriving
+    /// the decimal-place count from the result's own magnitude. Integer
+    /// digits are never rounded away, so a result with more than `n` digits
+    /// left of the point keeps all of them and no fractional digits. This is the
+    /// one that matters once a carry changes how many digits are needed to
+    /// the left of the point - fixed decimal places would silently report
+    /// more or fewer significant figures than the data actually has.
+    SignificantDigits(u32),
+    /// Preserve the input's own visible precision, exactly as [`increment`]
+    /// does.
+    Auto,
+}
+
+/// Increments `selected_text` by `amount` units of its own smallest decimal
+/// place, e.g. `"5.2"` + 1 -> `"5.3"`, `".5"` + 1 -> `".6"`, `"5."` + 1 ->
+/// `"6."`. The trailing-dot or leading-dot shorthand and the number of
+/// fractional digits are both preserved. Returns `None` for anything that
+/// isn't a plain (optionally signed) decimal literal, including a lone `.`.
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    let dot = selected_text.find('.')?;
+    let sign_len = usize::from(selected_text.starts_with('-'));
+    let leading_dot = dot == sign_len;
+    let has_trailing_dot = dot == selected_text.len() - 1;
+
+    let digits_before = &selected_text[sign_len..dot];
+    let digits_after = &selected_text[dot + 1..];
+    if !digits_before.chars().all(|c| c.is_ascii_digit())
+        || !digits_after.chars().all(|c| c.is_ascii_digit())
+        || (digits_before.is_empty() && digits_after.is_empty())
+    {
+        return None;
+    }
+
+    let decimals = digits_after.len() as u32;
+    let scale = 10i128.checked_pow(decimals)?;
+    let value: f64 = selected_text.parse().ok()?;
+    let scaled = (value * scale as f64).round() as i128;
+    let new_scaled = scaled.checked_add(amount as i128)?;
+
+    let mut rendered = format!(
+        "{:.*}",
+        decimals as usize,
+        new_scaled as f64 / scale as f64
+    );
+
+    // `{:.0}` formatting above drops a bare trailing dot entirely; put it
+    // back so `5.` stays `5.` rather than becoming `6`.
+    if has_trailing_dot && decimals == 0 {
+        rendered.push('.');
+    }
+    // A leading dot has no integer part; match that instead of the leading
+    // `0` the formatting above always renders.
+    if leading_dot {
+        if let Some(stripped) = rendered.strip_prefix("0.") {
+            rendered = format!(".{stripped}");
+        } else if let Some(stripped) = rendered.strip_prefix("-0.") {
+            rendered = format!("-.{stripped}");
+        }
+    }
+
+    Some(rendered)
+}
+
+/// Like [`increment`], but allows a thousands-style separator (see
+/// [`active_separator`]) in the integer part, e.g. `"1,000.000"`. Grouping
+/// only ever applies left of the decimal point - a separator found in the
+/// fractional part makes the whole literal invalid (`"1.00,0"` is
+/// rejected), since fractional digits are never grouped. Regroups every 3
+/// digits from the decimal point, so a carry that grows the integer part
+/// past its current grouping (e.g. `"999,999.9"` + 1 tenth) gets a new
+/// separator synthesized the same way [`super::integer::increment`] does.
+pub fn increment_grouped(selected_text: &str, amount: i64) -> Option<String> {
+    let dot = selected_text.find('.')?;
+    let separator = active_separator(selected_text);
+    if selected_text[dot + 1..].contains(separator) {
+        return None;
+    }
+
+    let ungrouped: String = selected_text.chars().filter(|&c| c != separator).collect();
+    let result = increment(&ungrouped, amount)?;
+
+    if !selected_text.contains(separator) {
+        return Some(result);
+    }
+
+    let (sign, rest) = match result.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", result.as_str()),
+    };
+    let (int_part, frac_part) = rest.split_once('.').unwrap_or((rest, ""));
+
+    let mut grouped = String::with_capacity(int_part.len() + int_part.len() / 3);
+    for (i, c) in int_part.chars().rev().enumerate() {
+        if i > 0 && i % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(c);
+    }
+    let grouped: String = grouped.chars().rev().collect();
+
+    Some(format!("{sign}{grouped}.{frac_part}"))
+}
+
+/// Moves `selected_text`'s decimal point by `places` - positive shifts it
+/// right (multiplying by `10.pow(places)`), negative shifts it left -
+/// padding with zeros as needed and preserving the sign. Distinct from
+/// [`increment`]'s digit-by-digit arithmetic: this is a pure unit-conversion
+/// reformatting, e.g. `"12.34"` shifted by `1` is `"123.4"`, not `"12.34"` +
+/// `1`. A shift that consumes every fractional digit leaves a bare trailing
+/// dot (`"99.9"` shifted by `1` is `"999."`), the same shorthand [`increment`]
+/// preserves on input. Returns `None` for anything that isn't a plain
+/// (optionally signed) decimal literal.
+pub fn shift_decimal(selected_text: &str, places: i32) -> Option<String> {
+    let dot = selected_text.find('.')?;
+    let sign_len = usize::from(selected_text.starts_with('-'));
+    let sign = &selected_text[..sign_len];
+
+    let digits_before = &selected_text[sign_len..dot];
+    let digits_after = &selected_text[dot + 1..];
+    if !digits_before.chars().all(|c| c.is_ascii_digit())
+        || !digits_after.chars().all(|c| c.is_ascii_digit())
+        || (digits_before.is_empty() && digits_after.is_empty())
+    {
+        return None;
+    }
+
+    let combined = format!("{digits_before}{digits_after}");
+    let new_point = digits_before.len() as i64 + places as i64;
+
+    let (int_part, frac_part) = if new_point <= 0 {
+        (
+            "0".to_string(),
+            format!("{}{combined}", "0".repeat((-new_point) as usize)),
+        )
+    } else if new_point as usize >= combined.len() {
+        (
+            format!("{combined}{}", "0".repeat(new_point as usize - combined.len())),
+            String::new(),
+        )
+    } else {
+        let split = new_point as usize;
+        (combined[..split].to_string(), combined[split..].to_string())
+    };
+
+    if frac_part.is_empty() {
+        Some(format!("{sign}{int_part}."))
+    } else {
+        Some(format!("{sign}{int_part}.{frac_part}"))
+    }
+}
+
+/// Like [`increment`], but `precision` controls how the result's fractional
+/// digits are derived from the incremented value rather than always
+/// preserving the input's own decimal-place count. `Precision::Auto` behaves
+/// exactly like [`increment`].
+pub fn increment_with_precision(
+    selected_text: &str,
+    amount: i64,
+    precision: Precision,
+) -> Option<String> {
+    if precision == Precision::Auto {
+        return increment(selected_text, amount);
+    }
+
+    let dot = selected_text.find('.')?;
+    let sign_len = usize::from(selected_text.starts_with('-'));
+
+    let digits_before = &selected_text[sign_len..dot];
+    let digits_after = &selected_text[dot + 1..];
+    if !digits_before.chars().all(|c| c.is_ascii_digit())
+        || !digits_after.chars().all(|c| c.is_ascii_digit())
+        || (digits_before.is_empty() && digits_after.is_empty())
+    {
+        return None;
+    }
+
+    let decimals = digits_after.len() as u32;
+    let scale = 10i128.checked_pow(decimals)?;
+    let value: f64 = selected_text.parse().ok()?;
+    let scaled = (value * scale as f64).round() as i128;
+    let new_scaled = scaled.checked_add(amount as i128)?;
+    let new_value = new_scaled as f64 / scale as f64;
+
+    let output_decimals = match precision {
+        Precision::DecimalPlaces(n) => n,
+        Precision::Auto => decimals,
+        Precision::SignificantDigits(n) => {
+            if new_value == 0.0 {
+                n.saturating_sub(1)
+            } else {
+                let magnitude = new_value.abs().log10().floor() as i64;
+                (n as i64 - 1 - magnitude).max(0) as u32
+            }
+        }
+    };
+
+    Some(format!("{:.*}", output_decimals as usize, new_value))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_plain_decimal() {
+        assert_eq!(increment("5.2", 1).unwrap(), "5.3");
+        assert_eq!(increment("5.29", 1).unwrap(), "5.30");
+        assert_eq!(increment("-5.2", 1).unwrap(), "-5.1");
+    }
+
+    #[test]
+    fn test_increment_trailing_dot() {
+        assert_eq!(increment("5.", 1).unwrap(), "6.");
+        assert_eq!(increment("-5.", 1).unwrap(), "-4.");
+    }
+
+    #[test]
+    fn test_increment_leading_dot() {
+        assert_eq!(increment(".5", 1).unwrap(), ".6");
+        assert_eq!(increment("-.5", -1).unwrap(), "-.6");
+        assert_eq!(increment("-.5", 1).unwrap(), "-.4");
+    }
+
+    #[test]
+    fn test_increment_grouped_keeps_separators_left_of_the_point() {
+        assert_eq!(
+            increment_grouped("1,000.000", 1).unwrap(),
+            "1,000.001"
+        );
+        // Carrying into a new group gets a new separator, same growth
+        // behavior as the plain integer path.
+        assert_eq!(
+            increment_grouped("999,999.9", 1).unwrap(),
+            "1,000,000.0"
+        );
+        // No separator at all: behaves exactly like the ungrouped path.
+        assert_eq!(increment_grouped("5.2", 1).unwrap(), increment("5.2", 1).unwrap());
+    }
+
+    #[test]
+    fn test_increment_grouped_rejects_separators_in_the_fractional_part() {
+        assert_eq!(increment_grouped("1.00,0", 1), None);
+        assert_eq!(increment_grouped("-1,000.0,5", 1), None);
+    }
+
+    #[test]
+    fn test_increment_rejects_non_decimals() {
+        assert_eq!(increment("5", 1), None);
+        assert_eq!(increment(".", 1), None);
+        assert_eq!(increment("-.", 1), None);
+        assert_eq!(increment("0x1.8", 1), None);
+    }
+
+    #[test]
+    fn test_increment_with_precision_decimal_places_keeps_fixed_width() {
+        // A carry that grows the integer part still gets the same number of
+        // decimal places, even though that means reporting a figure the
+        // input's own precision didn't actually support.
+        assert_eq!(
+            increment_with_precision("9.9", 1, Precision::DecimalPlaces(2)).unwrap(),
+            "10.00"
+        );
+    }
+
+    #[test]
+    fn test_increment_with_precision_significant_digits_tracks_magnitude() {
+        // Same input and amount as above, but asking for 3 significant
+        // figures instead: the decimal places shrink by one once the carry
+        // adds a digit to the left of the point.
+        assert_eq!(
+            increment_with_precision("9.9", 1, Precision::SignificantDigits(3)).unwrap(),
+            "10.0"
+        );
+        // No magnitude change: behaves like a plain fixed precision.
+        assert_eq!(
+            increment_with_precision("1.2", 1, Precision::SignificantDigits(3)).unwrap(),
+            "1.30"
+        );
+    }
+
+    #[test]
+    fn test_increment_with_precision_auto_matches_increment() {
+        assert_eq!(
+            increment_with_precision("5.29", 1, Precision::Auto).unwrap(),
+            increment("5.29", 1).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_shift_decimal_right_across_the_units_boundary() {
+        // 9.99 -> 99.9 -> 999.
+        assert_eq!(shift_decimal("9.99", 1).unwrap(), "99.9");
+        assert_eq!(shift_decimal("99.9", 1).unwrap(), "999.");
+    }
+
+    #[test]
+    fn test_shift_decimal_left_across_the_units_boundary() {
+        // The exact reverse of the right-shift chain above.
+        assert_eq!(shift_decimal("999.", -1).unwrap(), "99.9");
+        assert_eq!(shift_decimal("99.9", -1).unwrap(), "9.99");
+    }
+
+    #[test]
+    fn test_shift_decimal_pads_with_zeros_past_either_end() {
+        assert_eq!(shift_decimal("1.5", -3).unwrap(), "0.0015");
+        assert_eq!(shift_decimal("1.5", 3).unwrap(), "1500.");
+    }
+
+    #[test]
+    fn test_shift_decimal_preserves_sign() {
+        assert_eq!(shift_decimal("-1.23", 1).unwrap(), "-12.3");
+        assert_eq!(shift_decimal("-1.23", -1).unwrap(), "-0.123");
+    }
+
+    #[test]
+    fn test_shift_decimal_by_zero_is_a_no_op() {
+        assert_eq!(shift_decimal("12.34", 0).unwrap(), "12.34");
+    }
+
+    #[test]
+    fn test_shift_decimal_rejects_non_decimals() {
+        assert_eq!(shift_decimal("5", 1), None);
+        assert_eq!(shift_decimal(".", 1), None);
+    }
+}
@@ -0,0 +1,139 @@
+//! Increment for C99 hex float literals (`0x1.8p3`): a `0x`/`0X` prefix, hex
+//! digits with an optional `.` separating integer and fractional parts, and
+//! a `p`/`P`-introduced decimal exponent. Unlike [`super::integer`]'s plain
+//! hex literals, a hex float has two numeric parts a caller could mean to
+//! bump - the mantissa or the exponent - so which one to increment is an
+//! explicit choice rather than something this module guesses at.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Which part of a hex float literal [`increment`] should change.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexFloatField {
+    /// The hex digits (integer and fractional parts together, as one fixed-
+    /// point number), incremented by its smallest represented unit -
+    /// incrementing `0x1.8p3`'s mantissa carries into the integer part at
+    /// `0x1.Fp3` -> `0x2.0p3`, the same way decimal carry works.
+    Mantissa,
+    /// The decimal exponent after `p`/`P`.
+    Exponent,
+}
+
+static HEX_FLOAT: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(-)?0([xX])([0-9a-fA-F]*)(?:\.([0-9a-fA-F]*))?[pP]([+-]?[0-9]+)$").unwrap()
+});
+
+/// Increments `field` of a hex float literal by `amount`, preserving the
+/// literal's case (of the `0x`/`0X` prefix and hex digits independently) and
+/// digit width, growing either if the result no longer fits. Returns `None`
+/// for anything that isn't a hex float, or a mantissa with no digits at all.
+pub fn increment(selected_text: &str, amount: i64, field: HexFloatField) -> Option<String> {
+    let captures = HEX_FLOAT.captures(selected_text)?;
+    let sign = captures.get(1).map_or("", |m| m.as_str());
+    let x = captures.get(2).unwrap().as_str();
+    let int_part = captures.get(3).map_or("", |m| m.as_str());
+    let frac_part = captures.get(4).map_or("", |m| m.as_str());
+    let exponent = captures.get(5).unwrap().as_str();
+    if int_part.is_empty() && frac_part.is_empty() {
+        return None;
+    }
+    let has_dot = captures.get(4).is_some();
+    let p = &selected_text[captures.get(5).unwrap().start() - 1..captures.get(5).unwrap().start()];
+
+    match field {
+        HexFloatField::Mantissa => {
+            let digits = format!("{int_part}{frac_part}");
+            let value = u128::from_str_radix(&digits, 16).ok()?;
+            let new_value = (value as i128).saturating_add(amount as i128).max(0) as u128;
+
+            let (lower_count, upper_count): (usize, usize) =
+                digits.chars().fold((0, 0), |(lower, upper), c| {
+                    (
+                        lower + c.is_ascii_lowercase() as usize,
+                        upper + c.is_ascii_uppercase() as usize,
+                    )
+                });
+            let rendered = if upper_count > lower_count {
+                format!("{:01$X}", new_value, digits.len())
+            } else {
+                format!("{:01$x}", new_value, digits.len())
+            };
+
+            let frac_len = frac_part.len();
+            let split_at = rendered.len().saturating_sub(frac_len);
+            let (new_int, new_frac) = rendered.split_at(split_at);
+            let dot = if has_dot { "." } else { "" };
+            Some(format!("{sign}0{x}{new_int}{dot}{new_frac}{p}{exponent}"))
+        }
+        HexFloatField::Exponent => {
+            let value: i64 = exponent.parse().ok()?;
+            let new_value = value.saturating_add(amount);
+            let rendered = if exponent.starts_with('+') {
+                format!("+{new_value}")
+            } else {
+                format!("{new_value}")
+            };
+            Some(format!(
+                "{sign}0{x}{int_part}{}{frac_part}{p}{rendered}",
+                if has_dot { "." } else { "" }
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_mantissa_carries_into_the_integer_part() {
+        assert_eq!(
+            increment("0x1.8p3", 1, HexFloatField::Mantissa).unwrap(),
+            "0x1.9p3"
+        );
+        assert_eq!(
+            increment("0x1.Fp3", 1, HexFloatField::Mantissa).unwrap(),
+            "0x2.0p3"
+        );
+        // Case of the hex digits and the `0x`/`p` markers is preserved
+        // independently.
+        assert_eq!(
+            increment("0X1.Fp3", 1, HexFloatField::Mantissa).unwrap(),
+            "0X2.0p3"
+        );
+        assert_eq!(
+            increment("0x1.fP3", 1, HexFloatField::Mantissa).unwrap(),
+            "0x2.0P3"
+        );
+        // No fractional part: the integer digits are the whole mantissa.
+        assert_eq!(
+            increment("0x1p3", 1, HexFloatField::Mantissa).unwrap(),
+            "0x2p3"
+        );
+    }
+
+    #[test]
+    fn test_increment_exponent() {
+        assert_eq!(
+            increment("0x1.8p3", 1, HexFloatField::Exponent).unwrap(),
+            "0x1.8p4"
+        );
+        assert_eq!(
+            increment("0x1.8p3", -4, HexFloatField::Exponent).unwrap(),
+            "0x1.8p-1"
+        );
+        // An explicit `+` on the original exponent is kept.
+        assert_eq!(
+            increment("0x1.8p+3", 1, HexFloatField::Exponent).unwrap(),
+            "0x1.8p+4"
+        );
+    }
+
+    #[test]
+    fn test_increment_rejects_malformed_input() {
+        assert_eq!(increment("1.8p3", 1, HexFloatField::Mantissa), None);
+        assert_eq!(increment("0x1.8", 1, HexFloatField::Mantissa), None);
+        assert_eq!(increment("0x.p3", 1, HexFloatField::Mantissa), None);
+    }
+}
@@ -1,115 +1,1614 @@
+//! Invariant: every public entry point here takes arbitrary, possibly
+//! adversarial selected text (see `fuzz/fuzz_targets/increment.rs`) and must
+//! return `None` rather than panic. In practice that means preferring
+//! `str::get` over indexing/slicing whenever a byte offset computed against
+//! one string (e.g. a detected prefix length) is used against a different,
+//! separately-built string, and `saturating_sub`/`is_char_boundary` checks
+//! wherever a character count (like a count of separator occurrences) is
+//! used as a byte offset or could exceed the length it's subtracted from.
+
 const SEPARATOR: char = '_';
+/// Separator used by colon-grouped hex literals, e.g. MAC/EUI addresses
+/// (`aa:bb:cc:dd:ee:ff`).
+const COLON_SEPARATOR: char = ':';
+/// Non-breaking space, used like a regular space for SI/ISO digit grouping
+/// (`1\u{a0}000\u{a0}000`).
+const NBSP_SEPARATOR: char = '\u{00A0}';
+/// Comma, as used by some tools to group hex/octal/binary digits (`0xFF,FF`)
+/// instead of the usual `_`.
+const COMMA_SEPARATOR: char = ',';
+/// The Unicode minus sign (distinct from ASCII hyphen-minus `-`), as written
+/// by word processors and math typesetting for a negative decimal literal.
+const UNICODE_MINUS: char = '\u{2212}';
+
+/// Returns the separator character actually used by this literal: `:` for
+/// MAC/EUI-style colon grouping, `,` for comma-grouped hex/octal/binary, a
+/// space (regular or non-breaking) for SI/ISO digit grouping (`1 000 000`),
+/// `_` otherwise.
+pub(super) fn active_separator(selected_text: &str) -> char {
+    if selected_text.contains(COLON_SEPARATOR) {
+        COLON_SEPARATOR
+    } else if selected_text.contains(COMMA_SEPARATOR) {
+        COMMA_SEPARATOR
+    } else if selected_text.contains(NBSP_SEPARATOR) {
+        NBSP_SEPARATOR
+    } else if selected_text.contains(' ') {
+        ' '
+    } else {
+        SEPARATOR
+    }
+}
+
+/// Maps a single-letter radix-override micro-prefix to its radix.
+fn radix_override_value(letter: char) -> Option<u32> {
+    match letter {
+        'b' => Some(2),
+        'o' => Some(8),
+        'd' => Some(10),
+        'x' => Some(16),
+        _ => None,
+    }
+}
+
+/// Strips a `b:`/`o:`/`d:`/`x:` radix-override micro-prefix (e.g. `b:1010`,
+/// `o:777`), returning the radix it names and the remaining text with the
+/// micro-prefix removed. This is purely a parsing convenience for otherwise
+/// ambiguous digit strings (a bare `10` is normally decimal; `b:10` forces it
+/// to be read as binary) and the micro-prefix is never re-added to the
+/// output.
+///
+/// A real `0x`/`0o`/`0b`/`$`/`#` prefix always wins: those all start with a
+/// digit or symbol, so they never collide with this letter+colon pattern,
+/// and [`detect_prefix`] is only consulted when this returns `None`.
+fn strip_radix_override(selected_text: &str) -> Option<(u32, &str)> {
+    let mut chars = selected_text.chars();
+    let letter = chars.next()?;
+    if chars.next()? != COLON_SEPARATOR {
+        return None;
+    }
+    let radix = radix_override_value(letter)?;
+    Some((radix, &selected_text[2..]))
+}
+
+/// The prefix and radix detected at the start of a number literal.
+struct Prefix {
+    len: usize,
+    radix: u32,
+}
+
+/// Maps a number literal's prefix text to the base it encodes, for
+/// [`increment_with_prefixes`]'s base/prefix detection. `prefix` is matched
+/// with `starts_with`, so if one prefix is a superset of another (`#$` vs
+/// `#`) the longer one must come first in the slice.
+#[derive(Debug, Clone, Copy)]
+pub struct PrefixRule {
+    pub prefix: &'static str,
+    pub radix: u32,
+}
+
+/// The prefix table [`increment`] uses: `0x`/`0o`/`0b`/`0d` (Rust-style) and
+/// `#$`/`$`/`#` (Pascal/assembler-style hex).
+pub fn default_prefix_rules() -> Vec<PrefixRule> {
+    vec![
+        PrefixRule {
+            prefix: "0x",
+            radix: 16,
+        },
+        PrefixRule {
+            prefix: "0o",
+            radix: 8,
+        },
+        PrefixRule {
+            prefix: "0b",
+            radix: 2,
+        },
+        PrefixRule {
+            prefix: "0d",
+            radix: 10,
+        },
+        PrefixRule {
+            prefix: "#$",
+            radix: 16,
+        },
+        PrefixRule {
+            prefix: "$",
+            radix: 16,
+        },
+        PrefixRule {
+            prefix: "#",
+            radix: 16,
+        },
+    ]
+}
+
+/// Detects the prefix (if any) and the radix of a number literal, without
+/// validating that the remaining digits actually parse in that radix.
+fn detect_prefix(selected_text: &str) -> Prefix {
+    detect_prefix_with_rules(selected_text, &default_prefix_rules())
+}
+
+/// Like [`detect_prefix`], but checking `rules` instead of the built-in
+/// table.
+fn detect_prefix_with_rules(selected_text: &str, rules: &[PrefixRule]) -> Prefix {
+    for rule in rules {
+        if selected_text.starts_with(rule.prefix) {
+            return Prefix {
+                len: rule.prefix.len(),
+                radix: rule.radix,
+            };
+        }
+    }
+
+    if selected_text.contains(COLON_SEPARATOR)
+        && selected_text
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || c == COLON_SEPARATOR)
+    {
+        // Colon-grouped hex, e.g. a MAC/EUI address: no prefix, radix 16.
+        Prefix { len: 0, radix: 16 }
+    } else {
+        Prefix { len: 0, radix: 10 }
+    }
+}
+
+/// Information about a detected number literal, as produced by [analyze_number]
+/// without performing any arithmetic on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberInfo {
+    /// The radix of the number (2, 8, 10, or 16).
+    pub base: u32,
+    /// The prefix string (e.g. `"0x"`, `"$"`, `"#$"`, `"0d"`), empty when the
+    /// literal has none.
+    pub prefix: String,
+    /// Number of digit characters, excluding the prefix and separators.
+    pub width: usize,
+    /// Positions of the separator characters (see [`active_separator`]),
+    /// counted from the right of the literal.
+    pub separator_rtl_indexes: Vec<usize>,
+    /// The parsed value of the number.
+    pub value: i128,
+}
+
+/// Detects the base/prefix/width of a number literal without modifying it.
+/// Returns `None` for every input that [increment] rejects, and also for
+/// some that [increment] accepts: a decimal literal too large for an
+/// `i128`, a `-`-signed hex/octal/binary literal, and a literal written
+/// with the Unicode minus sign.
+pub fn analyze_number(selected_text: &str) -> Option<NumberInfo> {
+    if let Some((radix, remainder)) = strip_radix_override(selected_text) {
+        return analyze_number_radix(remainder, radix);
+    }
+
+    let separator = active_separator(selected_text);
+    if selected_text.is_empty()
+        || selected_text.ends_with(separator)
+        || selected_text.starts_with(separator)
+    {
+        return None;
+    }
+
+    let Prefix { len, radix } = detect_prefix(selected_text);
+
+    let separator_rtl_indexes: Vec<usize> = selected_text
+        .chars()
+        .rev()
+        .enumerate()
+        .filter_map(|(i, c)| if c == separator { Some(i) } else { None })
+        .collect();
+
+    let word: String = selected_text.chars().filter(|&c| c != separator).collect();
+    // Checked rather than a plain index - see the module-level
+    // non-panicking invariant.
+    let number = word.get(len..)?;
+    let value = if radix == 10 {
+        i128::from_str_radix(number, radix).ok()?
+    } else {
+        u128::from_str_radix(number, radix).ok()? as i128
+    };
+
+    Some(NumberInfo {
+        base: radix,
+        prefix: word.get(..len)?.to_string(),
+        width: number.len(),
+        separator_rtl_indexes,
+        value,
+    })
+}
+
+/// Like [`analyze_number`], but for text that already had its `b:`/`o:`/`d:`/`x:`
+/// radix-override micro-prefix stripped by [`strip_radix_override`].
+fn analyze_number_radix(remainder: &str, radix: u32) -> Option<NumberInfo> {
+    let separator = active_separator(remainder);
+    if remainder.is_empty() || remainder.ends_with(separator) || remainder.starts_with(separator) {
+        return None;
+    }
+
+    let separator_rtl_indexes: Vec<usize> = remainder
+        .chars()
+        .rev()
+        .enumerate()
+        .filter_map(|(i, c)| if c == separator { Some(i) } else { None })
+        .collect();
+
+    let word: String = remainder.chars().filter(|&c| c != separator).collect();
+    let value = if radix == 10 {
+        i128::from_str_radix(&word, radix).ok()?
+    } else {
+        u128::from_str_radix(&word, radix).ok()? as i128
+    };
+
+    Some(NumberInfo {
+        base: radix,
+        prefix: String::new(),
+        width: word.len(),
+        separator_rtl_indexes,
+        value,
+    })
+}
+
+/// A numeric base [convert_base] can re-encode a number literal into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base {
+    Binary,
+    Octal,
+    Decimal,
+    Hexadecimal,
+}
+
+impl Base {
+    fn prefix(self) -> &'static str {
+        match self {
+            Base::Binary => "0b",
+            Base::Octal => "0o",
+            Base::Decimal => "",
+            Base::Hexadecimal => "0x",
+        }
+    }
+}
+
+/// Renders `value` in `to`'s radix with its prefix, e.g. `0xff` from `255`
+/// hexadecimal. `None` if `value` is negative and `to` doesn't support sign
+/// (2/8/16 are unsigned in this module's conventions).
+fn render_in_base(value: i128, to: Base) -> Option<String> {
+    if value.is_negative() && to != Base::Decimal {
+        return None;
+    }
+
+    let rendered = match to {
+        Base::Binary => format!("{:b}", value),
+        Base::Octal => format!("{:o}", value),
+        Base::Decimal => format!("{}", value),
+        Base::Hexadecimal => format!("{:x}", value),
+    };
+    Some(format!("{}{rendered}", to.prefix()))
+}
+
+/// Converts the selected number to `to`, without incrementing it. Parses the
+/// input in its detected base (via [analyze_number]) and re-renders in the
+/// target base with the appropriate prefix; width and separators are
+/// dropped, since the digit count generally differs across bases.
+pub fn convert_base(selected_text: &str, to: Base) -> Option<String> {
+    let info = analyze_number(selected_text)?;
+    render_in_base(info.value, to)
+}
+
+/// Like [`increment`], but rendering the result in `to_base` instead of the
+/// input's own base, e.g. incrementing a decimal literal while writing the
+/// result out in hex. Parses in the detected base (via [analyze_number]),
+/// adds `amount` (saturating, same as [increment]), and renders in `to_base`
+/// via [convert_base]'s formatting rules. Since the input and output bases
+/// can differ, width and separator grouping are not preserved - there's no
+/// single width that makes sense in both.
+pub fn increment_convert(selected_text: &str, amount: i64, to_base: Base) -> Option<String> {
+    let info = analyze_number(selected_text)?;
+    let new_value = info.value.saturating_add(amount as i128);
+    render_in_base(new_value, to_base)
+}
+
+/// Which hex literal notation [`normalize_hex_prefix`] renders into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexPrefixStyle {
+    /// `0x1f` (Rust/C-style).
+    ZeroX,
+    /// `8'h1f` (Verilog-style, with an explicit bit width).
+    Verilog,
+}
+
+/// A hex literal's value and bit width, as detected by
+/// [`normalize_hex_prefix`] in either `0x` or Verilog `width'h` form.
+struct HexLiteral {
+    value: u128,
+    /// `Some` only for a Verilog literal, which carries its own width;
+    /// `None` for a `0x` literal, which has no notion of one.
+    width: Option<u32>,
+}
+
+/// Parses a Verilog-style `width'h<digits>` literal (e.g. `8'hFF`), which
+/// [`analyze_number`] doesn't know about.
+fn parse_verilog_hex(text: &str) -> Option<HexLiteral> {
+    let (width, rest) = text.split_once('\'')?;
+    let width: u32 = width.parse().ok()?;
+    let digits = rest.strip_prefix('h').or_else(|| rest.strip_prefix('H'))?;
+    if digits.is_empty() {
+        return None;
+    }
+    let value = u128::from_str_radix(digits, 16).ok()?;
+    Some(HexLiteral {
+        value,
+        width: Some(width),
+    })
+}
+
+/// Detects a hex number literal in either `0x`- or Verilog `'h`-prefixed
+/// form and re-emits it in `style`, without changing the value. Reuses
+/// [`analyze_number`] for the `0x` case; a width carried by a Verilog
+/// literal is kept when re-emitting in Verilog style and dropped when
+/// converting to `0x`, which has no width. A Verilog width is synthesized
+/// from the value's hex digit count (4 bits per digit) when the source had
+/// none.
+pub fn normalize_hex_prefix(text: &str, style: HexPrefixStyle) -> Option<String> {
+    let literal = match analyze_number(text) {
+        Some(info) if info.base == 16 && info.prefix == "0x" => HexLiteral {
+            value: info.value as u128,
+            width: None,
+        },
+        _ => parse_verilog_hex(text)?,
+    };
+
+    match style {
+        HexPrefixStyle::ZeroX => Some(format!("0x{:x}", literal.value)),
+        HexPrefixStyle::Verilog => {
+            let width = literal
+                .width
+                .unwrap_or_else(|| format!("{:x}", literal.value).len() as u32 * 4);
+            Some(format!("{width}'h{:x}", literal.value))
+        }
+    }
+}
 
 /// Increment an integer.
 ///
 /// Supported bases:
 ///     2 with prefix 0b
 ///     8 with prefix 0o
-///     10 with no prefix
-///     16 with prefix 0x
+///     10 with no prefix, or an explicit 0d prefix (preserved on output)
+///     16 with prefix 0x, $, #, or #$ (Pascal/assembler style)
 ///
 /// An integer can contain `_` as a separator but may not start or end with a separator.
-/// Base 10 integers can go negative, but bases 2, 8, and 16 cannot.
+/// A hex literal with no prefix can instead use `:` as a separator (e.g. a MAC/EUI
+/// address like `aa:bb:cc:dd:ee:ff`); the two separators are not mixed in one literal.
+/// Base 10 integers can go negative. Bases 2, 8, and 16 clamp at zero,
+/// unless the literal itself starts with `-` (e.g. `-0x10`), which is read
+/// as a sign-magnitude value that can cross zero and flip sign. A
+/// negative base 10 literal may use either the ASCII hyphen-minus or the
+/// Unicode minus sign (`−`, U+2212, as commonly produced by word
+/// processors); whichever one was used is preserved on output.
 /// All addition and subtraction is saturating.
+/// The exception is a base 10 value or sum that does not fit in an `i128`,
+/// which is computed with arbitrary-precision digit-string arithmetic.
+///
+/// A leading `b:`/`o:`/`d:`/`x:` micro-prefix overrides the detected radix
+/// for an otherwise-ambiguous digit string (e.g. `b:10` is binary `10`, not
+/// decimal) and is stripped from the output; see [`strip_radix_override`].
+/// A real `0x`/`0o`/`0b`/`$`/`#` prefix always takes precedence over this,
+/// since those prefixes can never start with the micro-prefix's letter.
 pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    increment_with_prefixes(selected_text, amount, &default_prefix_rules())
+}
+
+/// Like [`increment`], but detecting `rules` instead of the built-in prefix
+/// table, for number literal styles this module doesn't know about (e.g. a
+/// `%` binary prefix or `&H` hex prefix from some assembler dialect).
+/// `rules` is checked in the order given (see [`PrefixRule`]) and each
+/// matched prefix is preserved verbatim in the output.
+pub fn increment_with_prefixes(
+    selected_text: &str,
+    amount: i64,
+    rules: &[PrefixRule],
+) -> Option<String> {
+    increment_with_options(selected_text, amount, rules, true)
+}
+
+/// Like [`increment_with_prefixes`], but if `grow_separators` is `false`,
+/// skips the "insert a new separator once the result grows past its
+/// current grouping" step - existing separators are kept and repositioned,
+/// but no new one is synthesized, so `999_999 + 1` renders as `1000_000`
+/// instead of `1_000_000`. Lets users who hand-group their numbers keep
+/// full control over where separators land. `increment`/`increment_with_prefixes`
+/// both behave as if this were `true`.
+pub fn increment_with_separator_growth(
+    selected_text: &str,
+    amount: i64,
+    grow_separators: bool,
+) -> Option<String> {
+    increment_with_options(selected_text, amount, &default_prefix_rules(), grow_separators)
+}
+
+/// The result of [`increment_clamped`], reporting whether `amount` had to be
+/// clamped before it was applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClampedIncrement {
+    pub result: String,
+    /// The amount actually applied, after clamping.
+    pub applied_amount: i64,
+    /// Whether `amount` fell outside `cap` and had to be clamped to fit.
+    pub clamped: bool,
+}
+
+/// Like [`increment`], but if `cap` is given, clamps `amount` into that
+/// range before applying it and reports whether clamping occurred - a guard
+/// against a fat-fingered repeat count turning into a confusingly enormous
+/// jump. `cap: None` preserves [`increment`]'s behavior exactly.
+pub fn increment_clamped(
+    selected_text: &str,
+    amount: i64,
+    cap: Option<std::ops::RangeInclusive<i64>>,
+) -> Option<ClampedIncrement> {
+    let applied_amount = match &cap {
+        Some(range) => amount.clamp(*range.start(), *range.end()),
+        None => amount,
+    };
+    let result = increment(selected_text, applied_amount)?;
+    Some(ClampedIncrement {
+        result,
+        applied_amount,
+        clamped: applied_amount != amount,
+    })
+}
+
+/// The result of [`increment_aligned`], reporting whether the result still
+/// fits the original field width.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlignedIncrement {
+    pub result: String,
+    /// Whether the incremented number no longer fit within the selection's
+    /// original field width and had to grow past it, losing alignment with
+    /// the rest of the column.
+    pub overflowed: bool,
+}
+
+/// Like [`increment`], but for a number right-aligned in a fixed-width
+/// column with leading spaces (e.g. a line from a table of right-aligned
+/// values): keeps the total selection width constant by trimming or adding
+/// leading spaces as the number's digit count changes, instead of letting
+/// the field grow or shrink like [`increment`] does. Distinct from
+/// zero-padding ([`increment`] already preserves a leading-zero width on
+/// its own) - this is specifically about the spaces used for column
+/// alignment, which must never turn into digits. `selected_text` must have
+/// only leading spaces before the number (a sign is fine); `overflowed` is
+/// set instead of returning `None` when the result no longer fits, so the
+/// caller can still show the (now misaligned) value rather than silently
+/// refusing the edit.
+pub fn increment_aligned(selected_text: &str, amount: i64) -> Option<AlignedIncrement> {
+    let field_width = selected_text.chars().count();
+    let trimmed = selected_text.trim_start_matches(' ');
+    if trimmed.is_empty() {
+        return None;
+    }
+
+    let incremented = increment(trimmed, amount)?;
+    let new_width = incremented.chars().count();
+
+    if new_width <= field_width {
+        let padding = " ".repeat(field_width - new_width);
+        Some(AlignedIncrement {
+            result: format!("{padding}{incremented}"),
+            overflowed: false,
+        })
+    } else {
+        Some(AlignedIncrement {
+            result: incremented,
+            overflowed: true,
+        })
+    }
+}
+
+/// The result of [`increment_lenient`], reporting which separator character
+/// was actually detected (see [`active_separator`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LenientIncrement {
+    pub result: String,
+    pub detected_separator: char,
+}
+
+/// Like [`increment`], but reports which thousands separator character was
+/// actually used, for a caller that wants to flag "this number used `,` not
+/// `_`" rather than silently re-rendering it - useful for pasted numbers
+/// whose separator doesn't match this editor's own default. This doesn't
+/// change what [`increment`] accepts: a literal mixing more than one
+/// separator character (genuinely ambiguous grouping) still fails, the same
+/// as [`increment`]'s own `None`.
+pub fn increment_lenient(selected_text: &str, amount: i64) -> Option<LenientIncrement> {
+    let detected_separator = active_separator(selected_text);
+    let result = increment(selected_text, amount)?;
+    Some(LenientIncrement {
+        result,
+        detected_separator,
+    })
+}
+
+/// Rebuilds `digits` with `sep` inserted every `group_size` digits, counted
+/// from the right - a full regroup from scratch rather than patching the
+/// previous separator positions forward or backward. Growing or shrinking
+/// `digits` by any amount, including across a group boundary, can never
+/// leave a stray or misplaced separator behind this way, since the result
+/// only ever depends on `digits`' own length, not on where the separators
+/// used to sit. A `group_size` of `0` leaves `digits` untouched.
+fn regroup(digits: &str, group_size: usize, sep: char) -> String {
+    if group_size == 0 {
+        return digits.to_string();
+    }
+
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / group_size);
+    for (i, c) in digits.chars().rev().enumerate() {
+        if i > 0 && i % group_size == 0 {
+            grouped.push(sep);
+        }
+        grouped.push(c);
+    }
+    grouped.chars().rev().collect()
+}
+
+/// The digit-group size implied by where separators sat in the original
+/// literal - the gap between the two leftmost separators, or the lone
+/// separator's own distance from the end when there's only one. This is
+/// what [`regroup`] needs to rebuild the same grouping at a (possibly
+/// different) digit count.
+fn group_size_from_rtl_indexes(indexes: &[usize]) -> usize {
+    match indexes {
+        [.., b, a] => a - b - 1,
+        [only] => *only,
+        [] => 0,
+    }
+}
+
+/// Whether a re-rendered hex literal should use uppercase digits, matching
+/// whichever case was more common in `number` (ties favor lowercase, since
+/// `upper_count > lower_count` is a strict inequality).
+fn prefers_uppercase_hex(number: &str) -> bool {
+    let (lower_count, upper_count): (usize, usize) =
+        number.chars().fold((0, 0), |(lower, upper), c| {
+            (
+                lower + c.is_ascii_lowercase() as usize,
+                upper + c.is_ascii_uppercase() as usize,
+            )
+        });
+    upper_count > lower_count
+}
+
+fn increment_with_options(
+    selected_text: &str,
+    amount: i64,
+    rules: &[PrefixRule],
+    grow_separators: bool,
+) -> Option<String> {
+    if let Some((radix, remainder)) = strip_radix_override(selected_text) {
+        return increment_radix(remainder, amount, radix);
+    }
+
+    // A Unicode minus is otherwise invisible to every check below, which all
+    // key off the ASCII hyphen-minus; recurse on the ASCII-normalized text
+    // and swap the sign character back on the way out, so the rest of this
+    // function doesn't need to special-case it.
+    if let Some(magnitude_text) = selected_text.strip_prefix(UNICODE_MINUS) {
+        let ascii_text = format!("-{magnitude_text}");
+        let result = increment_with_options(&ascii_text, amount, rules, grow_separators)?;
+        return Some(match result.strip_prefix('-') {
+            Some(magnitude) => format!("{UNICODE_MINUS}{magnitude}"),
+            None => result,
+        });
+    }
+
+    if let Some(magnitude_text) = selected_text.strip_prefix('-') {
+        if detect_prefix_with_rules(magnitude_text, rules).radix != 10 {
+            return increment_signed_magnitude(magnitude_text, amount, rules, grow_separators);
+        }
+    }
+
+    let separator = active_separator(selected_text);
     if selected_text.is_empty()
-        || selected_text.ends_with(SEPARATOR)
-        || selected_text.starts_with(SEPARATOR)
+        || selected_text.ends_with(separator)
+        || selected_text.starts_with(separator)
     {
         return None;
     }
 
-    let radix = if selected_text.starts_with("0x") {
-        16
-    } else if selected_text.starts_with("0o") {
-        8
-    } else if selected_text.starts_with("0b") {
-        2
-    } else {
-        10
-    };
+    let Prefix {
+        len: prefix_len,
+        radix,
+    } = detect_prefix_with_rules(selected_text, rules);
 
     // Get separator indexes from right to left.
     let separator_rtl_indexes: Vec<usize> = selected_text
         .chars()
         .rev()
         .enumerate()
-        .filter_map(|(i, c)| if c == SEPARATOR { Some(i) } else { None })
+        .filter_map(|(i, c)| if c == separator { Some(i) } else { None })
         .collect();
 
-    let word: String = selected_text.chars().filter(|&c| c != SEPARATOR).collect();
+    let word: String = selected_text.chars().filter(|&c| c != separator).collect();
 
     let mut new_text = if radix == 10 {
-        let number = &word;
-        let value = i128::from_str_radix(number, radix).ok()?;
-        let new_value = value.saturating_add(amount as i128);
+        // Checked rather than a plain index: see the module-level
+        // non-panicking invariant. A `0d` prefix is the only way
+        // `prefix_len` is ever nonzero here; a plain decimal literal always
+        // has `prefix_len == 0`, so `prefix` is empty and this is the same
+        // as indexing `word` directly.
+        let prefix = word.get(..prefix_len)?;
+        let number = word.get(prefix_len..)?;
+        match i128::from_str_radix(number, radix).ok().and_then(|value| {
+            value.checked_add(amount as i128).map(|new_value| (value, new_value))
+        }) {
+            Some((value, new_value)) => {
+                let format_length = match (value.is_negative(), new_value.is_negative()) {
+                    (true, false) => number.len().saturating_sub(1),
+                    (false, true) => number.len() + 1,
+                    _ => number.len(),
+                }
+                .saturating_sub(separator_rtl_indexes.len());
 
-        let format_length = match (value.is_negative(), new_value.is_negative()) {
-            (true, false) => number.len() - 1,
-            (false, true) => number.len() + 1,
-            _ => number.len(),
-        } - separator_rtl_indexes.len();
+                if number.starts_with('0') || number.starts_with("-0") {
+                    format!("{prefix}{:01$}", new_value, format_length)
+                } else {
+                    format!("{prefix}{}", new_value)
+                }
+            }
+            // Number doesn't fit in an i128, or the sum of a number that does
+            // overflows it - either way, fall back to arbitrary-precision
+            // string arithmetic instead of silently failing or saturating.
+            None => format!("{prefix}{}", bignum_decimal_add(number, amount)?),
+        }
+    } else {
+        // Checked rather than a plain index: a custom `rules` entry (see
+        // [`increment_with_prefixes`]) whose prefix contains the active
+        // separator character would desync `prefix_len` (a byte offset into
+        // `selected_text`) from `word`'s own byte length - see the
+        // module-level non-panicking invariant.
+        let prefix = word.get(..prefix_len)?;
+        let number = word.get(prefix_len..)?;
+        let value = u128::from_str_radix(number, radix).ok()?;
+        let new_value = (value as i128).saturating_add(amount as i128);
+        let new_value = if new_value < 0 { 0 } else { new_value };
+        let format_length = selected_text
+            .len()
+            .saturating_sub(prefix_len)
+            .saturating_sub(separator_rtl_indexes.len());
+
+        match radix {
+            2 => format!("{prefix}{:01$b}", new_value, format_length),
+            8 => format!("{prefix}{:01$o}", new_value, format_length),
+            16 => {
+                if prefers_uppercase_hex(number) {
+                    format!("{prefix}{:01$X}", new_value, format_length)
+                } else {
+                    format!("{prefix}{:01$x}", new_value, format_length)
+                }
+            }
+            _ => unimplemented!("radix not supported: {}", radix),
+        }
+    };
 
-        if number.starts_with('0') || number.starts_with("-0") {
-            format!("{:01$}", new_value, format_length)
+    if !separator_rtl_indexes.is_empty() {
+        if grow_separators {
+            // Full regroup from scratch: correct regardless of whether the
+            // result grew, shrank, or crossed a group boundary in either
+            // direction, unlike patching the old separator positions
+            // forward or backward would be.
+            let group_size = group_size_from_rtl_indexes(&separator_rtl_indexes);
+            let head_len = if radix == 10 {
+                prefix_len + usize::from(new_text[prefix_len..].starts_with('-'))
+            } else {
+                prefix_len
+            };
+            let (head, digits) = new_text.split_at(head_len);
+            new_text = format!("{head}{}", regroup(digits, group_size, separator));
         } else {
-            format!("{}", new_value)
+            // Reposition the same separators that were already there,
+            // synthesizing no new one - `999_999 + 1` -> `1000_000`.
+            for &rtl_index in &separator_rtl_indexes {
+                if rtl_index < new_text.len() {
+                    let new_index = new_text.len().saturating_sub(rtl_index);
+                    // `rtl_index` is a character count but `new_index` is
+                    // used as a byte offset; guarded rather than inserted
+                    // unconditionally - see the module-level non-panicking
+                    // invariant.
+                    if new_index > 0 && new_text.is_char_boundary(new_index) {
+                        new_text.insert(new_index, separator);
+                    }
+                }
+            }
         }
+    }
+
+    Some(new_text)
+}
+
+/// Like [`increment`], but if `grouping` is given, the result's digits are
+/// regrouped every `grouping` digits with [`SEPARATOR`], regardless of
+/// whether the input was grouped. This is for "increment and also format"
+/// in one step, e.g. turning `1234567` (ungrouped input) into `1_234_567`.
+/// Any sign and prefix (`0x`, `$`, ...) are left outside the grouping.
+pub fn increment_with_grouping(
+    selected_text: &str,
+    amount: i64,
+    grouping: Option<usize>,
+) -> Option<String> {
+    let result = increment(selected_text, amount)?;
+    let Some(grouping) = grouping.filter(|&n| n > 0) else {
+        return Some(result);
+    };
+
+    let (sign, rest) = match result.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", result.as_str()),
+    };
+    let prefix_len = detect_prefix(rest).len;
+    let (prefix, digits) = rest.split_at(prefix_len);
+    // Drop any separators the input already had before regrouping.
+    let digits: String = digits.chars().filter(|c| c.is_ascii_alphanumeric()).collect();
+    let grouped = regroup(&digits, grouping, SEPARATOR);
+
+    Some(format!("{sign}{prefix}{grouped}"))
+}
+
+/// Increments only the leading numeric token of `selected_text`, preserving
+/// everything from the first whitespace onward untouched - e.g. a trailing
+/// comment or unit (`"42 // count"` + 1 -> `"43 // count"`). Distinct from
+/// [`increment_all`]'s general scan for numbers anywhere in the text: the
+/// leading token must be a complete, valid literal on its own (in any base
+/// [`increment`] detects), not just the first number found. `None` if
+/// `selected_text` has no leading token, or that token isn't a valid
+/// literal.
+pub fn increment_with_trailing_text(selected_text: &str, amount: i64) -> Option<String> {
+    let split_at = selected_text
+        .find(char::is_whitespace)
+        .unwrap_or(selected_text.len());
+    let (leading, trailing) = selected_text.split_at(split_at);
+    if leading.is_empty() {
+        return None;
+    }
+
+    let incremented = increment(leading, amount)?;
+    Some(format!("{incremented}{trailing}"))
+}
+
+/// Splits a CSS-style value like `-12.5px` into its numeric part (`-12.5`)
+/// and trailing unit (`px`), the unit being whatever non-numeric suffix
+/// follows the digits.
+fn split_unit(text: &str) -> (&str, &str) {
+    let end = text
+        .char_indices()
+        .find(|&(i, c)| !(c.is_ascii_digit() || c == '.' || (c == '-' && i == 0)))
+        .map_or(text.len(), |(i, _)| i);
+    text.split_at(end)
+}
+
+/// Moves `value` to the next (`amount > 0`) or previous (`amount < 0`)
+/// multiple of `multiple` in `amount.abs()` grid steps, rather than by
+/// `amount` itself - a value already sitting on a multiple still moves a
+/// full step, it never stays put.
+fn snap_to_multiple(value: i64, amount: i64, multiple: i64) -> Option<i64> {
+    let sign = amount.signum();
+    let remainder = value.rem_euclid(multiple);
+    let first_step = match (remainder == 0, sign > 0) {
+        (true, _) => multiple,
+        (false, true) => multiple - remainder,
+        (false, false) => remainder,
+    };
+    let extra_steps = amount.checked_abs()?.checked_sub(1)?.checked_mul(multiple)?;
+    value.checked_add(sign.checked_mul(first_step.checked_add(extra_steps)?)?)
+}
+
+/// Like [`increment`], but moves to the next/previous multiple of
+/// `multiple` in the direction of `amount` instead of adding `amount`
+/// directly - useful for keeping layout/CSS values on a grid (e.g.
+/// snapping to 8px increments). Works on bare integers and decimals alike,
+/// preserving a trailing unit suffix (`px`, `em`, `%`, ...) and the
+/// decimal's original number of fractional digits verbatim. A value
+/// already exactly on a multiple still moves a full step rather than
+/// staying put.
+pub fn increment_snapped(selected_text: &str, amount: i64, multiple: i64) -> Option<String> {
+    if multiple <= 0 || amount == 0 {
+        return None;
+    }
+
+    let (numeric, unit) = split_unit(selected_text);
+    if numeric.is_empty() || numeric == "-" {
+        return None;
+    }
+
+    match numeric.find('.') {
+        Some(dot) => {
+            let decimals = (numeric.len() - dot - 1) as u32;
+            let scale = 10i64.checked_pow(decimals)?;
+            let scaled_value = (numeric.parse::<f64>().ok()? * scale as f64).round() as i64;
+            let scaled_multiple = multiple.checked_mul(scale)?;
+            let new_scaled = snap_to_multiple(scaled_value, amount, scaled_multiple)?;
+            Some(format!(
+                "{:.*}{unit}",
+                decimals as usize,
+                new_scaled as f64 / scale as f64
+            ))
+        }
+        None => {
+            let value: i64 = numeric.parse().ok()?;
+            let new_value = snap_to_multiple(value, amount, multiple)?;
+            Some(format!("{new_value}{unit}"))
+        }
+    }
+}
+
+/// Like [`increment`], but computes the amount to add as
+/// `base_amount + step * index` instead of taking it directly - the
+/// arithmetic a multi-cursor "fill a series" command needs for each cursor
+/// to land on a different value (e.g. cursor `index` of a `1, 2, 3, ...`
+/// series) while routing through the same width/base preservation as a
+/// single [`increment`] call, rather than each cursor computing its own
+/// amount and risking diverging on how the result gets re-rendered.
+pub fn increment_series(
+    selected_text: &str,
+    base_amount: i64,
+    step: i64,
+    index: u32,
+) -> Option<String> {
+    let amount = base_amount.saturating_add(step.saturating_mul(index as i64));
+    increment(selected_text, amount)
+}
+
+/// A character that could plausibly be part of a number literal: an
+/// alphanumeric (digits plus hex letters and base-prefix letters like the
+/// `x` in `0x`), or one of the grouping separators. Space-separated SI
+/// grouping (`1 000`) deliberately isn't included, since in free text a
+/// space almost always means "separate token", not "digit group".
+fn is_token_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == SEPARATOR || c == COMMA_SEPARATOR || c == COLON_SEPARATOR
+}
+
+/// Like [`is_token_char`], but a comma only counts when it's acting as a
+/// digit-group separator (digits on both sides, e.g. the `,` in `1,000`) -
+/// otherwise it's ordinary prose punctuation (e.g. the `,` in `-1, b=2`)
+/// and shouldn't pull a following token into the current one.
+fn is_token_char_at(chars: &[(usize, char)], j: usize) -> bool {
+    let c = chars[j].1;
+    if c == COMMA_SEPARATOR {
+        let prev_digit = j.checked_sub(1).is_some_and(|p| chars[p].1.is_ascii_digit());
+        let next_digit = chars.get(j + 1).is_some_and(|&(_, c)| c.is_ascii_digit());
+        prev_digit && next_digit
+    } else {
+        is_token_char(c)
+    }
+}
+
+/// A number literal's location and shape within a larger piece of text, as
+/// found by [`find_number_span`], without having incremented it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberSpan {
+    /// Byte range of the literal within the text that was searched,
+    /// prefix included.
+    pub range: std::ops::Range<usize>,
+    /// The radix of the number (2, 8, 10, or 16), same convention as
+    /// [`NumberInfo::base`].
+    pub base: u32,
+    /// Whether the literal carried an explicit base prefix (`0x`, `0b`,
+    /// `0o`, `$`, `#`, `#$`), as opposed to a bare, prefix-less literal.
+    pub had_prefix: bool,
+}
+
+/// Finds the first number literal in `text`, tokenizing the same way
+/// [`increment_all`] does but without incrementing anything - useful for a
+/// "select nearest number" command that should snap a selection onto a
+/// literal before the user decides whether to increment it. When `text`
+/// contains more than one number, the leftmost one wins; this matters when
+/// two candidate tokens start at the same position under different
+/// tokenizations (e.g. a signed literal immediately after another number,
+/// `"1-2"`, where `-2` could be read as its own signed token) - the earlier
+/// scan position always takes precedence. Returns `None` if `text` has no
+/// number at all.
+pub fn find_number_span(text: &str) -> Option<NumberSpan> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+
+    let mut i = 0;
+    while i < chars.len() {
+        let (_, c) = chars[i];
+        let has_sign = c == '-' && chars.get(i + 1).is_some_and(|_| is_token_char_at(&chars, i + 1));
+        let token_start = i;
+        let mut j = if has_sign { i + 1 } else { i };
+        if j >= chars.len() || !is_token_char_at(&chars, j) {
+            i += 1;
+            continue;
+        }
+        while j < chars.len() && is_token_char_at(&chars, j) {
+            j += 1;
+        }
+
+        let start_byte = chars[token_start].0;
+        let end_byte = chars.get(j).map_or(text.len(), |&(byte, _)| byte);
+        let token = &text[start_byte..end_byte];
+
+        if let Some(info) = analyze_number(token) {
+            return Some(NumberSpan {
+                range: start_byte..end_byte,
+                base: info.base,
+                had_prefix: !info.prefix.is_empty(),
+            });
+        }
+
+        i = j.max(token_start + 1);
+    }
+
+    None
+}
+
+/// Finds every numeric token in `text` and increments each by `amount`,
+/// independently - useful for a spreadsheet-like fill across a selection
+/// with several numbers in it. Each token keeps whatever base it was
+/// already detected as ([`increment`] handles the base detection per
+/// token), so mixed decimal/hex/octal/binary/MAC-style literals in the
+/// same text are each incremented correctly. Tokens that don't parse as a
+/// number (plain words) are left untouched. Returns the rewritten text and
+/// the byte ranges, in the original `text`, of every token that actually
+/// changed - `None` if nothing in `text` was a number.
+pub fn increment_all(text: &str, amount: i64) -> Option<(String, Vec<std::ops::Range<usize>>)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut ranges = Vec::new();
+    let mut result = String::with_capacity(text.len());
+    let mut copied_up_to = 0;
+
+    let mut i = 0;
+    while i < chars.len() {
+        let (_, c) = chars[i];
+        let has_sign = c == '-' && chars.get(i + 1).is_some_and(|_| is_token_char_at(&chars, i + 1));
+        let token_start = i;
+        let mut j = if has_sign { i + 1 } else { i };
+        if j >= chars.len() || !is_token_char_at(&chars, j) {
+            i += 1;
+            continue;
+        }
+        while j < chars.len() && is_token_char_at(&chars, j) {
+            j += 1;
+        }
+
+        let start_byte = chars[token_start].0;
+        let end_byte = chars.get(j).map_or(text.len(), |&(byte, _)| byte);
+        let token = &text[start_byte..end_byte];
+
+        if let Some(incremented) = increment(token, amount) {
+            result.push_str(&text[copied_up_to..start_byte]);
+            result.push_str(&incremented);
+            copied_up_to = end_byte;
+            ranges.push(start_byte..end_byte);
+        }
+
+        i = j.max(token_start + 1);
+    }
+    result.push_str(&text[copied_up_to..]);
+
+    if ranges.is_empty() {
+        None
     } else {
-        let number = &word[2..];
+        Some((result, ranges))
+    }
+}
+
+/// Like [`increment`], but for text that already had its `b:`/`o:`/`d:`/`x:`
+/// radix-override micro-prefix stripped by [`strip_radix_override`]; `radix`
+/// is forced rather than detected, and nothing is re-added to the output in
+/// its place.
+fn increment_radix(remainder: &str, amount: i64, radix: u32) -> Option<String> {
+    let separator = active_separator(remainder);
+    if remainder.is_empty() || remainder.ends_with(separator) || remainder.starts_with(separator) {
+        return None;
+    }
+
+    let separator_rtl_indexes: Vec<usize> = remainder
+        .chars()
+        .rev()
+        .enumerate()
+        .filter_map(|(i, c)| if c == separator { Some(i) } else { None })
+        .collect();
+
+    let word: String = remainder.chars().filter(|&c| c != separator).collect();
+
+    let mut new_text = if radix == 10 {
+        let number = &word;
+        match i128::from_str_radix(number, radix) {
+            Ok(value) => {
+                let new_value = value.saturating_add(amount as i128);
+
+                let format_length = match (value.is_negative(), new_value.is_negative()) {
+                    (true, false) => number.len().saturating_sub(1),
+                    (false, true) => number.len() + 1,
+                    _ => number.len(),
+                }
+                .saturating_sub(separator_rtl_indexes.len());
+
+                if number.starts_with('0') || number.starts_with("-0") {
+                    format!("{:01$}", new_value, format_length)
+                } else {
+                    format!("{}", new_value)
+                }
+            }
+            Err(_) => bignum_decimal_add(number, amount)?,
+        }
+    } else {
+        let number = &word;
         let value = u128::from_str_radix(number, radix).ok()?;
         let new_value = (value as i128).saturating_add(amount as i128);
         let new_value = if new_value < 0 { 0 } else { new_value };
-        let format_length = selected_text.len() - 2 - separator_rtl_indexes.len();
+        let format_length = remainder.len().saturating_sub(separator_rtl_indexes.len());
 
         match radix {
-            2 => format!("0b{:01$b}", new_value, format_length),
-            8 => format!("0o{:01$o}", new_value, format_length),
+            2 => format!("{:01$b}", new_value, format_length),
+            8 => format!("{:01$o}", new_value, format_length),
             16 => {
-                let (lower_count, upper_count): (usize, usize) =
-                    number.chars().fold((0, 0), |(lower, upper), c| {
-                        (
-                            lower + c.is_ascii_lowercase() as usize,
-                            upper + c.is_ascii_uppercase() as usize,
-                        )
-                    });
-                if upper_count > lower_count {
-                    format!("0x{:01$X}", new_value, format_length)
+                if prefers_uppercase_hex(number) {
+                    format!("{:01$X}", new_value, format_length)
                 } else {
-                    format!("0x{:01$x}", new_value, format_length)
+                    format!("{:01$x}", new_value, format_length)
                 }
             }
-            _ => unimplemented!("radix not supported: {}", radix),
+            _ => unreachable!("radix_override_value only produces 2, 8, 10, or 16"),
+        }
+    };
+
+    if !separator_rtl_indexes.is_empty() {
+        // Full regroup from scratch, same as [`increment_with_options`]'s
+        // default (`grow_separators: true`) path - correct regardless of
+        // whether the result grew or shrank across a group boundary.
+        let group_size = group_size_from_rtl_indexes(&separator_rtl_indexes);
+        let head_len = if radix == 10 {
+            usize::from(new_text.starts_with('-'))
+        } else {
+            0
+        };
+        let (head, digits) = new_text.split_at(head_len);
+        new_text = format!("{head}{}", regroup(digits, group_size, separator));
+    }
+
+    Some(new_text)
+}
+
+/// Renders `value` in `radix` (2..=16) as `width` digits, zero-padded on the
+/// left; never truncates below its natural digit count even if that's wider
+/// than `width`. `uppercase` selects `A`-`F` vs `a`-`f` for radices above 10.
+fn render_based_digits(mut value: u128, radix: u32, width: usize, uppercase: bool) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let mut out = Vec::new();
+    while value > 0 {
+        let digit = DIGITS[(value % radix as u128) as usize];
+        out.push(if uppercase {
+            digit.to_ascii_uppercase()
+        } else {
+            digit
+        });
+        value /= radix as u128;
+    }
+    while out.len() < width {
+        out.push(b'0');
+    }
+    out.reverse();
+    String::from_utf8(out).expect("digits are all ASCII")
+}
+
+/// Increments an Ada/VHDL based literal (`base#digits#`, e.g. `16#FF#`,
+/// `2#1010#`), a notation [`analyze_number`] doesn't recognize since the
+/// radix is spelled out as a decimal number rather than a fixed prefix
+/// symbol. Width and digit case are preserved; the base and `#...#` wrapper
+/// are re-emitted unchanged. `None` for a malformed literal or a radix
+/// outside `2..=16`, VHDL's own valid range. Saturates at zero rather than
+/// going negative, matching [`increment`]'s other unsigned bases.
+pub fn increment_based_literal(selected_text: &str, amount: i64) -> Option<String> {
+    let (base_str, rest) = selected_text.split_once('#')?;
+    let digits = rest.strip_suffix('#')?;
+    if digits.is_empty() {
+        return None;
+    }
+    let radix: u32 = base_str.parse().ok()?;
+    if !(2..=16).contains(&radix) {
+        return None;
+    }
+    if !digits.chars().all(|c| c.is_digit(radix)) {
+        return None;
+    }
+
+    let uppercase = digits.chars().any(|c| c.is_ascii_uppercase());
+    let value = u128::from_str_radix(digits, radix).ok()?;
+    let new_value = (value as i128).saturating_add(amount as i128);
+    let new_value = if new_value < 0 { 0 } else { new_value as u128 };
+
+    let rendered = render_based_digits(new_value, radix, digits.len(), uppercase);
+    Some(format!("{base_str}#{rendered}#"))
+}
+
+/// Increments a `0b` binary literal like [`increment`], then zero-pads the
+/// result's width up to a "natural" size for editing register masks: the
+/// next power of two starting from 8 bits (8, 16, 32, ...), or, if
+/// `multiple_of` is given, the next multiple of that instead. Never shrinks
+/// a value that already came out wider than the target - e.g. an overflow
+/// that grew the literal by a bit is left alone rather than truncated back
+/// down to fit.
+pub fn increment_binary_padded(
+    selected_text: &str,
+    amount: i64,
+    multiple_of: Option<usize>,
+) -> Option<String> {
+    if detect_prefix(selected_text).radix != 2 {
+        return None;
+    }
+    let result = increment(selected_text, amount)?;
+    let digits = result.len() - 2;
+
+    let target = match multiple_of {
+        Some(n) if n > 0 => ((digits + n - 1) / n) * n,
+        _ => {
+            let mut width = 8;
+            while width < digits {
+                width *= 2;
+            }
+            width
+        }
+    };
+    if target <= digits {
+        return Some(result);
+    }
+
+    Some(format!("0b{}{}", "0".repeat(target - digits), &result[2..]))
+}
+
+/// Flips a single bit of a `0b`/`0x` literal, re-rendering with the same
+/// width, case, and prefix. This is distinct from [`increment`]: it's a bit
+/// toggle rather than arithmetic, useful for editing bitfield registers.
+/// `bit_index` is counted from the least-significant bit (0-based). Returns
+/// `None` for decimal/octal literals, or an out-of-range `bit_index`.
+pub fn toggle_bit(selected_text: &str, bit_index: u32) -> Option<String> {
+    let Prefix {
+        len: prefix_len,
+        radix,
+    } = detect_prefix(selected_text);
+    if radix != 2 && radix != 16 {
+        return None;
+    }
+
+    let prefix = &selected_text[..prefix_len];
+    let number = &selected_text[prefix_len..];
+    let value = u128::from_str_radix(number, radix).ok()?;
+    if bit_index >= 128 || bit_index >= number.len() as u32 * (if radix == 2 { 1 } else { 4 }) {
+        return None;
+    }
+
+    let new_value = value ^ (1u128 << bit_index);
+    let format_length = number.len();
+
+    let new_text = match radix {
+        2 => format!("{prefix}{:01$b}", new_value, format_length),
+        16 => {
+            if prefers_uppercase_hex(number) {
+                format!("{prefix}{:01$X}", new_value, format_length)
+            } else {
+                format!("{prefix}{:01$x}", new_value, format_length)
+            }
+        }
+        _ => unreachable!("checked above"),
+    };
+
+    Some(new_text)
+}
+
+/// Rotates a fixed-width `0b`/`0x` literal's bits left (positive `amount`)
+/// or right (negative), preserving width/case/prefix. The width is implied
+/// by the digit count (`4` bits per hex digit, `1` per binary digit) -
+/// there's no support for an explicit Verilog-style width prefix; only
+/// [`normalize_hex_prefix`] reads those literals. Unlike [`increment`],
+/// this never changes the literal's width.
+pub fn rotate(selected_text: &str, amount: i64) -> Option<String> {
+    let Prefix {
+        len: prefix_len,
+        radix,
+    } = detect_prefix(selected_text);
+    if radix != 2 && radix != 16 {
+        return None;
+    }
+
+    let prefix = &selected_text[..prefix_len];
+    let number = &selected_text[prefix_len..];
+    if number.is_empty() {
+        return None;
+    }
+    let value = u128::from_str_radix(number, radix).ok()?;
+    let bits_per_digit = if radix == 2 { 1 } else { 4 };
+    let width = number.len() as u32 * bits_per_digit;
+    if width == 0 || width > 128 {
+        return None;
+    }
+
+    let mask = if width == 128 { u128::MAX } else { (1u128 << width) - 1 };
+    let value = value & mask;
+    let shift = amount.rem_euclid(width as i64) as u32;
+    let new_value = if shift == 0 {
+        value
+    } else {
+        ((value << shift) | (value >> (width - shift))) & mask
+    };
+
+    let format_length = number.len();
+    let new_text = match radix {
+        2 => format!("{prefix}{:01$b}", new_value, format_length),
+        16 => {
+            if prefers_uppercase_hex(number) {
+                format!("{prefix}{:01$X}", new_value, format_length)
+            } else {
+                format!("{prefix}{:01$x}", new_value, format_length)
+            }
+        }
+        _ => unreachable!("checked above"),
+    };
+
+    Some(new_text)
+}
+
+/// Which endpoint [`BitRangeMode::Endpoint`] adjusts in
+/// [`increment_bit_range`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitRangeEndpoint {
+    Msb,
+    Lsb,
+}
+
+/// How [`increment_bit_range`] applies `amount` to a `[msb:lsb]` range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitRangeMode {
+    /// Shifts the whole window: both `msb` and `lsb` move by `amount`,
+    /// keeping its width fixed.
+    WindowShift,
+    /// Moves only one endpoint, narrowing or widening the range.
+    Endpoint(BitRangeEndpoint),
+}
+
+/// Increments an HDL-style bit-range slice (`[msb:lsb]`, e.g. `[7:0]`,
+/// `[15:8]`), as used to index a bus/register field. `mode` selects whether
+/// both endpoints shift together (a window move, e.g. duplicating a bus
+/// slice at a new offset) or only one does (narrowing/widening the range,
+/// e.g. for the endpoint under the cursor). `None` for a malformed range
+/// (non-numeric endpoints) or one where `msb < lsb` or `lsb < 0`, before or
+/// after applying `amount`.
+pub fn increment_bit_range(selected_text: &str, amount: i64, mode: BitRangeMode) -> Option<String> {
+    let inner = selected_text
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))?;
+    let (msb_str, lsb_str) = inner.split_once(':')?;
+    let msb: i64 = msb_str.trim().parse().ok()?;
+    let lsb: i64 = lsb_str.trim().parse().ok()?;
+    if msb < lsb || lsb < 0 {
+        return None;
+    }
+
+    let (new_msb, new_lsb) = match mode {
+        BitRangeMode::WindowShift => (msb.saturating_add(amount), lsb.saturating_add(amount)),
+        BitRangeMode::Endpoint(BitRangeEndpoint::Msb) => (msb.saturating_add(amount), lsb),
+        BitRangeMode::Endpoint(BitRangeEndpoint::Lsb) => (msb, lsb.saturating_add(amount)),
+    };
+    if new_msb < new_lsb || new_lsb < 0 {
+        return None;
+    }
+
+    Some(format!("[{new_msb}:{new_lsb}]"))
+}
+
+/// Increments a fixed-width `0b`/`0x` literal as a two's-complement signed
+/// integer, wrapping at both ends of its width (e.g. an 8-bit `0xFF` plus `1`
+/// wraps to `0x00`, and `0x00` minus `1` wraps to `0xFF`) rather than
+/// clamping at zero the way [`increment`] does for these bases. The width is
+/// implied by the digit count, same as [`rotate`]/[`toggle_bit`]. This is an
+/// explicit, distinct mode rather than something [`increment`] falls back
+/// to, since a bare hex/binary literal is otherwise ambiguous between
+/// "unsigned register" and "signed fixed-width" interpretations.
+pub fn increment_signed_fixed_width(selected_text: &str, amount: i64) -> Option<String> {
+    let Prefix {
+        len: prefix_len,
+        radix,
+    } = detect_prefix(selected_text);
+    if radix != 2 && radix != 16 {
+        return None;
+    }
+
+    let prefix = &selected_text[..prefix_len];
+    let number = &selected_text[prefix_len..];
+    if number.is_empty() {
+        return None;
+    }
+    let value = u128::from_str_radix(number, radix).ok()?;
+    let bits_per_digit = if radix == 2 { 1 } else { 4 };
+    let width = number.len() as u32 * bits_per_digit;
+    if width == 0 || width > 128 {
+        return None;
+    }
+
+    let mask = if width == 128 {
+        u128::MAX
+    } else {
+        (1u128 << width) - 1
+    };
+    // n-bit two's-complement addition wraps exactly like unsigned modular
+    // addition of the same bit pattern, so `amount`'s two's-complement bit
+    // pattern (via the i128 -> u128 reinterpret) can just be added directly.
+    let delta = amount as i128 as u128;
+    let new_value = (value & mask).wrapping_add(delta) & mask;
+
+    let format_length = number.len();
+    let new_text = match radix {
+        2 => format!("{prefix}{:01$b}", new_value, format_length),
+        16 => {
+            if prefers_uppercase_hex(number) {
+                format!("{prefix}{:01$X}", new_value, format_length)
+            } else {
+                format!("{prefix}{:01$x}", new_value, format_length)
+            }
         }
+        _ => unreachable!("checked above"),
+    };
+
+    Some(new_text)
+}
+
+/// Expands a 3- or 4-digit octal permission mode (`644`, `0o644`, `4755`)
+/// into its symbolic form (`rw-r--r--`), including the setuid/setgid/sticky
+/// bits carried by a leading 4th digit. Meant to pair with incrementing an
+/// octal mode, so a caller can show what the new value actually grants.
+/// `None` for anything that isn't a 3- or 4-digit octal string, with or
+/// without a `0o`/`0O` prefix.
+pub fn octal_mode_symbolic(text: &str) -> Option<String> {
+    let digits = text
+        .strip_prefix("0o")
+        .or_else(|| text.strip_prefix("0O"))
+        .unwrap_or(text);
+    if digits.len() != 3 && digits.len() != 4 {
+        return None;
+    }
+    if !digits.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
+        return None;
+    }
+
+    let (special, perm) = if digits.len() == 4 {
+        (digits.as_bytes()[0] - b'0', &digits[1..])
+    } else {
+        (0, digits)
+    };
+    let setuid = special & 0b100 != 0;
+    let setgid = special & 0b010 != 0;
+    let sticky = special & 0b001 != 0;
+
+    let mut symbolic = String::with_capacity(9);
+    for (i, byte) in perm.bytes().enumerate() {
+        let bits = byte - b'0';
+        symbolic.push(if bits & 0b100 != 0 { 'r' } else { '-' });
+        symbolic.push(if bits & 0b010 != 0 { 'w' } else { '-' });
+        let exec = bits & 0b001 != 0;
+        symbolic.push(match (i, exec) {
+            (0, true) if setuid => 's',
+            (0, false) if setuid => 'S',
+            (1, true) if setgid => 's',
+            (1, false) if setgid => 'S',
+            (2, true) if sticky => 't',
+            (2, false) if sticky => 'T',
+            (_, true) => 'x',
+            (_, false) => '-',
+        });
+    }
+    Some(symbolic)
+}
+
+/// Increments a `-0x10`/`-0o17`/`-0b101`-style sign-magnitude literal:
+/// `magnitude_text` is the part after the leading `-` (so still carrying its
+/// own base prefix). Unlike plain hex/octal/binary, which clamp at zero,
+/// this can cross zero and flip sign, since the `-` is an explicit sign
+/// rather than two's-complement wraparound.
+fn increment_signed_magnitude(
+    magnitude_text: &str,
+    amount: i64,
+    rules: &[PrefixRule],
+    grow_separators: bool,
+) -> Option<String> {
+    let Prefix {
+        len: prefix_len,
+        radix,
+    } = detect_prefix_with_rules(magnitude_text, rules);
+
+    let separator = active_separator(magnitude_text);
+    if magnitude_text.is_empty()
+        || magnitude_text.ends_with(separator)
+        || magnitude_text.starts_with(separator)
+    {
+        return None;
+    }
+
+    let separator_rtl_indexes: Vec<usize> = magnitude_text
+        .chars()
+        .rev()
+        .enumerate()
+        .filter_map(|(i, c)| if c == separator { Some(i) } else { None })
+        .collect();
+
+    let word: String = magnitude_text.chars().filter(|&c| c != separator).collect();
+    // Checked rather than a plain index - see the module-level
+    // non-panicking invariant.
+    let prefix = word.get(..prefix_len)?;
+    let number = word.get(prefix_len..)?;
+    // Checked rather than cast/subtracted directly - see the module-level
+    // non-panicking invariant.
+    let magnitude = i128::try_from(u128::from_str_radix(number, radix).ok()?).ok()?;
+
+    // The literal's sign is negative, so adding `amount` to it is
+    // `-magnitude + amount`.
+    let signed_value = (amount as i128).checked_sub(magnitude)?;
+    let (out_negative, out_magnitude) = if signed_value < 0 {
+        (true, signed_value.unsigned_abs())
+    } else {
+        (false, signed_value as u128)
+    };
+
+    let format_length = magnitude_text
+        .len()
+        .saturating_sub(prefix_len)
+        .saturating_sub(separator_rtl_indexes.len());
+
+    let mut rendered = match radix {
+        2 => format!("{prefix}{:01$b}", out_magnitude, format_length),
+        8 => format!("{prefix}{:01$o}", out_magnitude, format_length),
+        16 => {
+            if prefers_uppercase_hex(number) {
+                format!("{prefix}{:01$X}", out_magnitude, format_length)
+            } else {
+                format!("{prefix}{:01$x}", out_magnitude, format_length)
+            }
+        }
+        _ => unreachable!("checked by detect_prefix's radix != 10 guard"),
     };
 
-    // Add separators from original number.
     for &rtl_index in &separator_rtl_indexes {
-        if rtl_index < new_text.len() {
-            let new_index = new_text.len().saturating_sub(rtl_index);
-            if new_index > 0 {
-                new_text.insert(new_index, SEPARATOR);
+        if rtl_index < rendered.len() {
+            let new_index = rendered.len().saturating_sub(rtl_index);
+            // `rtl_index` is a character count but `new_index` is used as a
+            // byte offset; guarded rather than inserted unconditionally -
+            // see the module-level non-panicking invariant.
+            if new_index > 0 && rendered.is_char_boundary(new_index) {
+                rendered.insert(new_index, separator);
             }
         }
     }
 
-    // Add in additional separators if necessary.
-    if new_text.len() > selected_text.len() && !separator_rtl_indexes.is_empty() {
+    if grow_separators && rendered.len() > magnitude_text.len() && !separator_rtl_indexes.is_empty()
+    {
         let spacing = match separator_rtl_indexes.as_slice() {
             [.., b, a] => a - b - 1,
             _ => separator_rtl_indexes[0],
         };
 
-        let prefix_length = if radix == 10 { 0 } else { 2 };
-        if let Some(mut index) = new_text.find(SEPARATOR) {
-            while index - prefix_length > spacing {
+        if let Some(mut index) = rendered.find(separator) {
+            // Compared as `index > prefix_len + spacing` rather than
+            // `index - prefix_len > spacing`, so a separator at or before
+            // the prefix can't underflow the subtraction. `spacing` is a character
+            // count but `index` is a byte offset, so the subtraction is
+            // also re-checked against `is_char_boundary` before inserting -
+            // see the module-level non-panicking invariant.
+            while spacing > 0 && index > prefix_len + spacing {
                 index -= spacing;
-                new_text.insert(index, SEPARATOR);
+                if !rendered.is_char_boundary(index) {
+                    break;
+                }
+                rendered.insert(index, separator);
             }
         }
     }
 
-    Some(new_text)
+    Some(if out_negative {
+        format!("-{rendered}")
+    } else {
+        rendered
+    })
+}
+
+/// Adds `amount` to an arbitrary-precision decimal digit string, used as a
+/// fallback when the number doesn't fit in an `i128`. Preserves leading
+/// zeros the way the fast path does.
+fn bignum_decimal_add(number: &str, amount: i64) -> Option<String> {
+    let negative = number.starts_with('-');
+    let magnitude = if negative { &number[1..] } else { number };
+    if !magnitude.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let amount_negative = amount < 0;
+    let amount_magnitude = amount.unsigned_abs().to_string();
+
+    let (result_negative, mut result_magnitude) = if negative == amount_negative {
+        (negative, bignum_add(magnitude, &amount_magnitude))
+    } else if bignum_cmp(magnitude, &amount_magnitude).is_ge() {
+        (negative, bignum_sub(magnitude, &amount_magnitude))
+    } else {
+        (amount_negative, bignum_sub(&amount_magnitude, magnitude))
+    };
+
+    // `0` is never negative.
+    let result_negative = result_negative && result_magnitude.chars().any(|c| c != '0');
+
+    if magnitude.starts_with('0') {
+        while result_magnitude.len() < magnitude.len() {
+            result_magnitude.insert(0, '0');
+        }
+    }
+
+    Some(if result_negative {
+        format!("-{result_magnitude}")
+    } else {
+        result_magnitude
+    })
+}
+
+/// Compares two non-negative decimal digit strings numerically.
+fn bignum_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    let a = a.trim_start_matches('0');
+    let b = b.trim_start_matches('0');
+    a.len().cmp(&b.len()).then_with(|| a.cmp(b))
+}
+
+/// Adds two non-negative decimal digit strings.
+fn bignum_add(a: &str, b: &str) -> String {
+    let mut result: Vec<u8> = Vec::with_capacity(a.len().max(b.len()) + 1);
+    let mut carry = 0u8;
+    let mut a = a.bytes().rev();
+    let mut b = b.bytes().rev();
+    loop {
+        let da = a.next();
+        let db = b.next();
+        if da.is_none() && db.is_none() && carry == 0 {
+            break;
+        }
+        let sum = da.map_or(0, |d| d - b'0') + db.map_or(0, |d| d - b'0') + carry;
+        result.push(b'0' + sum % 10);
+        carry = sum / 10;
+    }
+    result.reverse();
+    String::from_utf8(result).unwrap()
+}
+
+/// Subtracts non-negative decimal digit string `b` from `a`, assuming `a >= b`.
+fn bignum_sub(a: &str, b: &str) -> String {
+    // Strip leading zeros so `a`'s length actually reflects its magnitude;
+    // otherwise a zero-padded `a` could look "shorter" in digits than `b`.
+    let a = {
+        let trimmed = a.trim_start_matches('0');
+        if trimmed.is_empty() {
+            "0"
+        } else {
+            trimmed
+        }
+    };
+    let b = b.trim_start_matches('0');
+    let mut result: Vec<u8> = Vec::with_capacity(a.len());
+    let mut borrow = 0i8;
+    let mut a = a.bytes().rev();
+    let mut b = b.bytes().rev();
+    loop {
+        let da = a.next();
+        if da.is_none() {
+            break;
+        }
+        let db = b.next();
+        let mut diff = (da.unwrap() - b'0') as i8 - db.map_or(0, |d| (d - b'0') as i8) - borrow;
+        if diff < 0 {
+            diff += 10;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+        result.push(b'0' + diff as u8);
+    }
+    while result.len() > 1 && *result.last().unwrap() == b'0' {
+        result.pop();
+    }
+    result.reverse();
+    String::from_utf8(result).unwrap()
 }
 
 #[cfg(test)]
@@ -136,17 +1635,83 @@ fn test_increment_basic_decimal_numbers() {
     }
 
     #[test]
-    fn test_increment_basic_hexadecimal_numbers() {
+    fn test_increment_unicode_minus_sign() {
+        let tests = [
+            ("\u{2212}5", 1, "\u{2212}4"),
+            ("\u{2212}5", 10, "5"),
+            ("\u{2212}1", 1, "0"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_basic_hexadecimal_numbers() {
+        let tests = [
+            ("0x0100", 1, "0x0101"),
+            ("0x0100", -1, "0x00ff"),
+            ("0x0001", -1, "0x0000"),
+            ("0x0000", -1, "0x0000"),
+            ("0xffffffffffffffff", 1, "0x10000000000000000"),
+            ("0xffffffffffffffff", 2, "0x10000000000000001"),
+            ("0xffffffffffffffff", -1, "0xfffffffffffffffe"),
+            ("0xABCDEF1234567890", 1, "0xABCDEF1234567891"),
+            ("0xabcdef1234567890", 1, "0xabcdef1234567891"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_dollar_and_hash_hexadecimal_numbers() {
+        let tests = [
+            ("$1A", 1, "$1B"),
+            ("$FF", 1, "$100"),
+            ("#1A", 1, "#1B"),
+            ("#$1A", 1, "#$1B"),
+            ("#$ff", 1, "#$100"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_explicit_0d_decimal_prefix() {
+        let tests = [
+            ("0d099", 1, "0d100"),
+            ("0d42", 1, "0d43"),
+            ("0d007", -1, "0d006"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_0d_prefix_not_confused_with_hex_or_plain_number() {
+        // A `0d` prefix is base 10, not `0x`'s base 16, even though both
+        // start with `0` followed by a letter.
+        assert_eq!(increment("0d10", 1).unwrap(), "0d11");
+        assert_eq!(increment("0x10", 1).unwrap(), "0x11");
+
+        // A plain number has no prefix to preserve.
+        assert_eq!(increment("10", 1).unwrap(), "11");
+    }
+
+    #[test]
+    fn test_increment_colon_grouped_mac_hex() {
         let tests = [
-            ("0x0100", 1, "0x0101"),
-            ("0x0100", -1, "0x00ff"),
-            ("0x0001", -1, "0x0000"),
-            ("0x0000", -1, "0x0000"),
-            ("0xffffffffffffffff", 1, "0x10000000000000000"),
-            ("0xffffffffffffffff", 2, "0x10000000000000001"),
-            ("0xffffffffffffffff", -1, "0xfffffffffffffffe"),
-            ("0xABCDEF1234567890", 1, "0xABCDEF1234567891"),
-            ("0xabcdef1234567890", 1, "0xabcdef1234567891"),
+            ("aa:bb:cc:dd:ee:ff", 1, "aa:bb:cc:dd:ef:00"),
+            ("AA:BB:CC:DD:EE:FE", 1, "AA:BB:CC:DD:EE:FF"),
+            ("00:00:00:00:00:ff", 1, "00:00:00:00:01:00"),
+            ("ff:ff", 1, "1:00:00"),
         ];
 
         for (original, amount, expected) in tests {
@@ -208,6 +1773,21 @@ fn test_increment_basic_binary_numbers() {
         }
     }
 
+    #[test]
+    fn test_increment_zero_literal_width() {
+        let tests = [
+            ("0", 0, "0"),
+            ("0", -1, "-1"),
+            ("00", 1, "01"),
+            ("00", -1, "-01"),
+            ("000", -1, "-001"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
     #[test]
     fn test_increment_with_separators() {
         let tests = [
@@ -219,6 +1799,30 @@ fn test_increment_with_separators() {
             ("0x0000_0000_0000", -1, "0x0000_0000_0000"),
             ("0b01111111_11111111", 1, "0b10000000_00000000"),
             ("0b11111111_11111111", 1, "0b1_00000000_00000000"),
+            ("0d999_999", 1, "0d1_000_000"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_separator_growth_boundaries() {
+        // Covers the digit-count boundary where regrouping must insert a new
+        // separator, in both directions and across bases (the separator math
+        // used to do an unsigned-subtraction-then-compare that could
+        // underflow once a separator landed at or before the prefix).
+        let tests = [
+            // Octal: growing from 4 to 6 digits needs a brand-new separator,
+            // not just the one carried over from the original text.
+            ("0o17_77", 31745, "0o10_00_00"),
+            // Hex: shrinking back down keeps the original grouping.
+            ("0x10_00", -1, "0x0f_ff"),
+            // A separator placed immediately after the prefix (so it's at
+            // `prefix_len` once re-added) used to be the case most likely to
+            // trip the underflow; regression-test it directly.
+            ("0x_1234", 1043916, "0x10_0000"),
         ];
 
         for (original, amount, expected) in tests {
@@ -226,10 +1830,693 @@ fn test_increment_with_separators() {
         }
     }
 
+    #[test]
+    fn test_regroup_crosses_boundaries_cleanly_in_both_directions() {
+        let tests = [
+            // Shrinking below a group boundary leaves no orphaned or
+            // misplaced separator.
+            ("1_000", -2, "998"),
+            // Shrinking across two group boundaries at once.
+            ("1_000_000", -2, "999_998"),
+            // Growing across a group boundary still regroups from scratch
+            // rather than just inserting one more separator.
+            ("999_998", 3, "1_000_001"),
+            // Negative decimals regroup the same way.
+            ("-1_000", 2, "-998"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_with_space_separators() {
+        let tests = [
+            ("1 000", 1, "1 001"),
+            ("999 999", 1, "1 000 000"),
+            ("1\u{a0}000", 1, "1\u{a0}001"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_with_comma_grouped_hex() {
+        let tests = [
+            ("0x00,01", 1, "0x00,02"),
+            ("0xFF,FF", -1, "0xFF,FE"),
+            ("0xFF,FF", 1, "0x1,00,00"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_with_grouping() {
+        let tests = [
+            ("1234567", 0, Some(3), "1_234_567"),
+            ("-123456", 0, Some(3), "-123_456"),
+            ("0x1234567", 0, Some(4), "0x123_4567"),
+            ("1234567", 1, None, "1234568"),
+        ];
+
+        for (original, amount, grouping, expected) in tests {
+            assert_eq!(
+                increment_with_grouping(original, amount, grouping).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_increment_with_trailing_text() {
+        assert_eq!(
+            increment_with_trailing_text("42 // count", 1).unwrap(),
+            "43 // count"
+        );
+        assert_eq!(
+            increment_with_trailing_text("0x1f unit", -1).unwrap(),
+            "0x1e unit"
+        );
+        // No trailing text at all is just a plain increment.
+        assert_eq!(increment_with_trailing_text("42", 1).unwrap(), "43");
+    }
+
+    #[test]
+    fn test_increment_with_trailing_text_rejects_invalid_leading_token() {
+        assert_eq!(increment_with_trailing_text("// count", 1), None);
+        assert_eq!(increment_with_trailing_text("abc 42", 1), None);
+        assert_eq!(increment_with_trailing_text("", 1), None);
+    }
+
+    #[test]
+    fn test_increment_snapped() {
+        let tests = [
+            ("23", 1, 8, "24"),
+            ("23", -1, 8, "16"),
+            // Already on a multiple: still moves a full step, not a no-op.
+            ("24", 1, 8, "32"),
+            ("24", -1, 8, "16"),
+            // Multiple grid steps at once.
+            ("23", 2, 8, "32"),
+            ("23", -2, 8, "8"),
+            // Unit suffix is preserved verbatim.
+            ("23px", 1, 8, "24px"),
+            ("-3px", 1, 8, "0px"),
+            // Decimals keep their original precision.
+            ("2.5", 1, 1, "3.0"),
+            ("1.25em", -1, 1, "1.00em"),
+        ];
+
+        for (original, amount, multiple, expected) in tests {
+            assert_eq!(increment_snapped(original, amount, multiple).unwrap(), expected);
+        }
+
+        assert_eq!(increment_snapped("23", 1, 0), None);
+        assert_eq!(increment_snapped("23", 0, 8), None);
+        assert_eq!(increment_snapped("not a number", 1, 8), None);
+    }
+
     #[test]
     fn test_leading_and_trailing_separators_arent_a_match() {
         assert_eq!(increment("9_", 1), None);
         assert_eq!(increment("_9", 1), None);
         assert_eq!(increment("_9_", 1), None);
     }
+
+    #[test]
+    fn test_increment_binary_padded() {
+        let tests = [
+            ("0b101", 1, None, "0b00000110"),
+            ("0b00000001", -1, None, "0b00000000"),
+            ("0b11111111", 1, None, "0b0000000100000000"),
+            ("0b101", 1, Some(4), "0b0110"),
+        ];
+
+        for (original, amount, multiple_of, expected) in tests {
+            assert_eq!(
+                increment_binary_padded(original, amount, multiple_of).unwrap(),
+                expected
+            );
+        }
+
+        // Not a binary literal: no padding mode applies.
+        assert_eq!(increment_binary_padded("0x0101", 1, None), None);
+        assert_eq!(increment_binary_padded("101", 1, None), None);
+    }
+
+    #[test]
+    fn test_increment_all_handles_mixed_bases_in_one_string() {
+        let (result, ranges) = increment_all("qty=5 hex=0x1f oct=0o17 word=foo", 1).unwrap();
+        assert_eq!(result, "qty=6 hex=0x20 oct=0o20 word=foo");
+        assert_eq!(ranges, vec![4..5, 10..14, 19..23]);
+    }
+
+    #[test]
+    fn test_increment_all_handles_negative_numbers_and_commas() {
+        let (result, ranges) = increment_all("a=-1, b=1,000", 1).unwrap();
+        assert_eq!(result, "a=0, b=1,001");
+        assert_eq!(ranges, vec![2..4, 8..13]);
+    }
+
+    #[test]
+    fn test_increment_all_returns_none_when_nothing_is_numeric() {
+        assert_eq!(increment_all("no numbers here", 1), None);
+    }
+
+    // `increment` has no `'b`/`'h`/`'d` Verilog-style sized-literal support
+    // (`8'hFF`; only `normalize_hex_prefix` reads those), so the
+    // partial-regex-match failure mode these guard against doesn't apply
+    // here: `detect_prefix` only recognizes the fixed prefixes of
+    // `default_prefix_rules` (plus colon-grouped hex), and every base -
+    // including the radix-override micro-prefix - is parsed with
+    // `{i128,u128}::from_str_radix`/`bignum_decimal_add`, both of which
+    // already reject the *whole* string if any character doesn't belong to
+    // that radix, rather than matching a valid prefix and silently ignoring
+    // trailing garbage.
+    #[test]
+    fn test_pathological_inputs_resembling_sized_literals_are_rejected() {
+        assert_eq!(increment("0bza", 1), None);
+        assert_eq!(increment("12'x", 1), None);
+        assert_eq!(increment("'q5", 1), None);
+    }
+
+    #[test]
+    fn test_increment_radix_override_prefix() {
+        // The `b:`/`o:`/`d:`/`x:` micro-prefix selects the radix but is
+        // stripped from the output, unlike a real `0x`/`0b` prefix.
+        let tests = [
+            ("b:1010", 1, "1011"),
+            ("o:777", 1, "1000"),
+            ("d:10", 1, "11"),
+            ("x:ff", 1, "100"),
+            ("b:00000100", -1, "00000011"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_numbers_beyond_i128() {
+        let tests = [
+            (
+                "100000000000000000000000000000000000000",
+                1,
+                "100000000000000000000000000000000000001",
+            ),
+            (
+                "170141183460469231731687303715884105727",
+                1,
+                "170141183460469231731687303715884105728",
+            ),
+            ("-99999999999999999999999999999999999999", -1, "-100000000000000000000000000000000000000"),
+            ("000000000000000000000000000000000000001", -1, "000000000000000000000000000000000000000"),
+            ("000000000000000000000000000000000000001", -2, "-000000000000000000000000000000000000001"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_negative_hex_sign_magnitude() {
+        let tests = [
+            // Stays negative: magnitude just grows/shrinks.
+            ("-0x10", 1, "-0x0f"),
+            ("-0x01", -2, "-0x03"),
+            // Crosses zero exactly.
+            ("-0x05", 5, "0x00"),
+            // Crosses zero and flips to positive.
+            ("-0x03", 10, "0x07"),
+            ("-0b0011", 10, "0b0111"),
+            // Magnitude growth needs a brand-new separator, same boundary
+            // case as the unsigned path.
+            ("-0x10_00", -70000, "-0x1_21_70"),
+            // Accounting-register style: the magnitude's fixed width is
+            // preserved whether or not the result crosses zero, and the `-`
+            // is present only while the value is negative.
+            ("-0x0A", 5, "-0x05"),
+            ("-0x0A", 20, "0x0A"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_toggle_bit() {
+        let tests = [
+            ("0xff", 0, "0xfe"),
+            ("0xff", 4, "0xef"),
+            ("0x00", 7, "0x80"),
+            ("0b1010", 0, "0b1011"),
+            ("0b1010", 1, "0b1000"),
+        ];
+
+        for (original, bit_index, expected) in tests {
+            assert_eq!(toggle_bit(original, bit_index).unwrap(), expected);
+        }
+
+        assert_eq!(toggle_bit("0xff", 8), None);
+        assert_eq!(toggle_bit("0b1010", 4), None);
+        assert_eq!(toggle_bit("100", 0), None);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let tests = [
+            ("0b10000001", 1, "0b00000011"),
+            ("0b10000001", -1, "0b11000000"),
+            ("0xF0", 4, "0x0F"),
+            ("0xf0", 4, "0x0f"),
+            // A full rotation is a no-op.
+            ("0b1010", 4, "0b1010"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(rotate(original, amount).unwrap(), expected);
+        }
+
+        // Decimal has no fixed width to rotate within.
+        assert_eq!(rotate("100", 1), None);
+    }
+
+    #[test]
+    fn test_increment_bit_range_window_shift() {
+        assert_eq!(
+            increment_bit_range("[7:0]", 1, BitRangeMode::WindowShift).unwrap(),
+            "[8:1]"
+        );
+        assert_eq!(
+            increment_bit_range("[15:8]", -8, BitRangeMode::WindowShift).unwrap(),
+            "[7:0]"
+        );
+    }
+
+    #[test]
+    fn test_increment_bit_range_single_endpoint() {
+        assert_eq!(
+            increment_bit_range("[7:0]", 1, BitRangeMode::Endpoint(BitRangeEndpoint::Msb))
+                .unwrap(),
+            "[8:0]"
+        );
+        assert_eq!(
+            increment_bit_range("[7:0]", 1, BitRangeMode::Endpoint(BitRangeEndpoint::Lsb))
+                .unwrap(),
+            "[7:1]"
+        );
+    }
+
+    #[test]
+    fn test_increment_bit_range_rejects_malformed_or_invalid_ranges() {
+        assert_eq!(increment_bit_range("[a:0]", 1, BitRangeMode::WindowShift), None);
+        assert_eq!(increment_bit_range("7:0", 1, BitRangeMode::WindowShift), None);
+        // msb < lsb, even before applying amount.
+        assert_eq!(increment_bit_range("[0:7]", 1, BitRangeMode::WindowShift), None);
+        // Narrowing past the other endpoint.
+        assert_eq!(
+            increment_bit_range("[7:0]", 10, BitRangeMode::Endpoint(BitRangeEndpoint::Lsb)),
+            None
+        );
+        // Would go negative.
+        assert_eq!(
+            increment_bit_range("[7:0]", -1, BitRangeMode::WindowShift),
+            None
+        );
+    }
+
+    #[test]
+    fn test_increment_signed_fixed_width() {
+        // Exhaustive 4-bit wrap cycle: every value increments to the next,
+        // crossing -1 (0b1111) -> 0 (0b0000) -> 1 (0b0001) correctly, and
+        // wrapping from the top (0b0111, +7) back around to the bottom
+        // (0b1000, -8).
+        for value in 0..16u32 {
+            let text = format!("0b{value:04b}");
+            let expected = format!("0b{:04b}", (value + 1) % 16);
+            assert_eq!(increment_signed_fixed_width(&text, 1).unwrap(), expected);
+        }
+
+        let tests = [
+            // 8-bit: -1 (0xFF) + 1 wraps to 0 (0x00).
+            ("0xFF", 1, "0x00"),
+            // 8-bit: 0 (0x00) - 1 wraps to -1 (0xff). No case signal in the
+            // input digits, so it ties to lowercase, same as `rotate`.
+            ("0x00", -1, "0xff"),
+            // 8-bit: most negative (-128, 0x80) - 1 wraps to most positive
+            // (127, 0x7f).
+            ("0x80", -1, "0x7f"),
+            // Lowercase is preserved.
+            ("0xff", 1, "0x00"),
+        ];
+        for (original, amount, expected) in tests {
+            assert_eq!(
+                increment_signed_fixed_width(original, amount).unwrap(),
+                expected
+            );
+        }
+
+        // Decimal/octal have no fixed width, so no two's-complement reading.
+        assert_eq!(increment_signed_fixed_width("100", 1), None);
+        assert_eq!(increment_signed_fixed_width("0o17", 1), None);
+    }
+
+    #[test]
+    fn test_convert_base() {
+        assert_eq!(convert_base("255", Base::Hexadecimal).unwrap(), "0xff");
+        assert_eq!(convert_base("0xff", Base::Decimal).unwrap(), "255");
+        assert_eq!(convert_base("0xff", Base::Binary).unwrap(), "0b11111111");
+        assert_eq!(convert_base("0b1010", Base::Octal).unwrap(), "0o12");
+        assert_eq!(convert_base("-5", Base::Hexadecimal), None);
+        assert_eq!(convert_base("-5", Base::Decimal).unwrap(), "-5");
+    }
+
+    #[test]
+    fn test_increment_convert() {
+        assert_eq!(increment_convert("15", 1, Base::Hexadecimal).unwrap(), "0x10");
+        assert_eq!(increment_convert("0xff", 1, Base::Decimal).unwrap(), "256");
+        assert_eq!(increment_convert("0b1010", 2, Base::Octal).unwrap(), "0o14");
+        // Negative results can't be rendered in an unsigned base.
+        assert_eq!(increment_convert("0", -1, Base::Hexadecimal), None);
+        // ...but decimal is signed, so it's fine there.
+        assert_eq!(increment_convert("0", -1, Base::Decimal).unwrap(), "-1");
+    }
+
+    #[test]
+    fn test_normalize_hex_prefix_to_verilog_synthesizes_width_from_digit_count() {
+        assert_eq!(
+            normalize_hex_prefix("0xff", HexPrefixStyle::Verilog).unwrap(),
+            "8'hff"
+        );
+        assert_eq!(
+            normalize_hex_prefix("0x1", HexPrefixStyle::Verilog).unwrap(),
+            "4'h1"
+        );
+    }
+
+    #[test]
+    fn test_normalize_hex_prefix_to_zero_x_preserves_value_and_drops_width() {
+        assert_eq!(
+            normalize_hex_prefix("8'hFF", HexPrefixStyle::ZeroX).unwrap(),
+            "0xff"
+        );
+        assert_eq!(
+            normalize_hex_prefix("16'h1f", HexPrefixStyle::ZeroX).unwrap(),
+            "0x1f"
+        );
+    }
+
+    #[test]
+    fn test_normalize_hex_prefix_round_trip_preserves_value_not_verilog_width() {
+        assert_eq!(
+            normalize_hex_prefix("16'hff", HexPrefixStyle::ZeroX).unwrap(),
+            "0xff"
+        );
+        // Converting a 0x literal back to Verilog with no remembered width
+        // re-synthesizes it from digit count instead, so it's not a true
+        // round trip of the *width* - only of the value.
+        assert_eq!(
+            normalize_hex_prefix("0xff", HexPrefixStyle::Verilog).unwrap(),
+            "8'hff"
+        );
+    }
+
+    #[test]
+    fn test_normalize_hex_prefix_rejects_non_hex_input() {
+        assert_eq!(normalize_hex_prefix("255", HexPrefixStyle::ZeroX), None);
+        assert_eq!(normalize_hex_prefix("0b1010", HexPrefixStyle::Verilog), None);
+        assert_eq!(normalize_hex_prefix("8'hzz", HexPrefixStyle::ZeroX), None);
+    }
+
+    #[test]
+    fn test_octal_mode_symbolic_three_digit() {
+        assert_eq!(octal_mode_symbolic("644").unwrap(), "rw-r--r--");
+        assert_eq!(octal_mode_symbolic("0o755").unwrap(), "rwxr-xr-x");
+        assert_eq!(octal_mode_symbolic("000").unwrap(), "---------");
+    }
+
+    #[test]
+    fn test_octal_mode_symbolic_four_digit_special_bits() {
+        // setuid with owner-exec set renders lowercase `s`.
+        assert_eq!(octal_mode_symbolic("4755").unwrap(), "rwsr-xr-x");
+        // setgid without group-exec set renders uppercase `S`.
+        assert_eq!(octal_mode_symbolic("2644").unwrap(), "rw-r-Sr--");
+        // sticky with other-exec set renders lowercase `t`.
+        assert_eq!(octal_mode_symbolic("1777").unwrap(), "rwxrwxrwt");
+        // all three special bits together.
+        assert_eq!(octal_mode_symbolic("7777").unwrap(), "rwsrwsrwt");
+    }
+
+    #[test]
+    fn test_octal_mode_symbolic_rejects_invalid_input() {
+        assert_eq!(octal_mode_symbolic("64"), None);
+        assert_eq!(octal_mode_symbolic("99999"), None);
+        assert_eq!(octal_mode_symbolic("0o648"), None);
+        assert_eq!(octal_mode_symbolic("abc"), None);
+    }
+
+    #[test]
+    fn test_increment_based_literal() {
+        assert_eq!(
+            increment_based_literal("16#FF#", 1).unwrap(),
+            "16#100#"
+        );
+        assert_eq!(
+            increment_based_literal("2#1010#", 1).unwrap(),
+            "2#1011#"
+        );
+        // Width preserved when the result still fits.
+        assert_eq!(increment_based_literal("8#777#", -1).unwrap(), "8#776#");
+        // Case preserved.
+        assert_eq!(increment_based_literal("16#ff#", 1).unwrap(), "16#100#");
+        // Saturates at zero rather than going negative.
+        assert_eq!(increment_based_literal("2#0#", -5).unwrap(), "2#0#");
+    }
+
+    #[test]
+    fn test_increment_based_literal_rejects_invalid_input() {
+        assert_eq!(increment_based_literal("FF", 1), None);
+        assert_eq!(increment_based_literal("1#11#", 1), None);
+        assert_eq!(increment_based_literal("17#11#", 1), None);
+        assert_eq!(increment_based_literal("16##", 1), None);
+        assert_eq!(increment_based_literal("16#GG#", 1), None);
+    }
+
+    #[test]
+    fn test_increment_with_prefixes() {
+        let rules = [
+            PrefixRule {
+                prefix: "&H",
+                radix: 16,
+            },
+            PrefixRule {
+                prefix: "%",
+                radix: 2,
+            },
+        ];
+
+        assert_eq!(
+            increment_with_prefixes("&H0F", 1, &rules).unwrap(),
+            "&H10"
+        );
+        assert_eq!(
+            increment_with_prefixes("%0011", 1, &rules).unwrap(),
+            "%0100"
+        );
+        // The built-in table isn't consulted once a custom one is given: a
+        // `0x`-prefixed literal isn't in `rules`, so it's read as (invalid)
+        // decimal instead.
+        assert_eq!(increment_with_prefixes("0x0F", 1, &rules), None);
+        assert_eq!(
+            increment("0x0F", 1).unwrap(),
+            increment_with_prefixes("0x0F", 1, &default_prefix_rules()).unwrap()
+        );
+
+        // Still applies to negative sign-magnitude literals.
+        assert_eq!(
+            increment_with_prefixes("-%0001", 1, &rules).unwrap(),
+            "%0000"
+        );
+    }
+
+    #[test]
+    fn test_increment_with_separator_growth() {
+        // Default (and `increment`/`increment_with_prefixes`): a new
+        // separator is synthesized once the result outgrows the original
+        // grouping.
+        assert_eq!(increment("999_999", 1).unwrap(), "1_000_000");
+        assert_eq!(
+            increment_with_separator_growth("999_999", 1, true).unwrap(),
+            "1_000_000"
+        );
+
+        // With growth disabled, the existing separator is kept where it
+        // was (so the newly-grown leading digit joins the group to its
+        // left) instead of a new one being inserted.
+        assert_eq!(
+            increment_with_separator_growth("999_999", 1, false).unwrap(),
+            "1000_000"
+        );
+
+        // A result that doesn't outgrow its grouping is unaffected either
+        // way, since the growth block never triggers.
+        assert_eq!(
+            increment_with_separator_growth("1_234", 1, false).unwrap(),
+            "1_235"
+        );
+    }
+
+    #[test]
+    fn test_increment_clamped() {
+        let result = increment_clamped("0", i64::MAX, Some(-10..=10)).unwrap();
+        assert_eq!(result.result, "10");
+        assert_eq!(result.applied_amount, 10);
+        assert!(result.clamped);
+
+        let result = increment_clamped("0", i64::MIN, Some(-10..=10)).unwrap();
+        assert_eq!(result.result, "-10");
+        assert_eq!(result.applied_amount, -10);
+        assert!(result.clamped);
+
+        // Within range: applied unchanged, not reported as clamped.
+        let result = increment_clamped("5", 3, Some(-10..=10)).unwrap();
+        assert_eq!(result.result, "8");
+        assert_eq!(result.applied_amount, 3);
+        assert!(!result.clamped);
+
+        // No cap preserves `increment`'s own (saturating) behavior exactly.
+        let result = increment_clamped("0", i64::MAX, None).unwrap();
+        assert_eq!(result.result, increment("0", i64::MAX).unwrap());
+        assert_eq!(result.applied_amount, i64::MAX);
+        assert!(!result.clamped);
+    }
+
+    #[test]
+    fn test_increment_aligned_keeps_field_width() {
+        // Growing a digit: one less leading space, same total width.
+        let result = increment_aligned("  99", 1).unwrap();
+        assert_eq!(result.result, " 100");
+        assert!(!result.overflowed);
+
+        // Shrinking a digit: one more leading space, same total width.
+        let result = increment_aligned(" 100", -1).unwrap();
+        assert_eq!(result.result, "  99");
+        assert!(!result.overflowed);
+
+        // No width change: padding untouched.
+        let result = increment_aligned("   5", 1).unwrap();
+        assert_eq!(result.result, "   6");
+        assert!(!result.overflowed);
+
+        // A sign is part of the number, not alignment padding.
+        let result = increment_aligned("  -1", -1).unwrap();
+        assert_eq!(result.result, "  -2");
+        assert!(!result.overflowed);
+    }
+
+    #[test]
+    fn test_increment_aligned_reports_overflow_past_field_width() {
+        let result = increment_aligned("99", 1).unwrap();
+        assert_eq!(result.result, "100");
+        assert!(result.overflowed);
+    }
+
+    #[test]
+    fn test_increment_aligned_rejects_blank_selection() {
+        assert_eq!(increment_aligned("   ", 1), None);
+        assert_eq!(increment_aligned("", 1), None);
+    }
+
+    #[test]
+    fn test_increment_lenient_reports_detected_separator() {
+        let result = increment_lenient("1,234", 1).unwrap();
+        assert_eq!(result.result, "1,235");
+        assert_eq!(result.detected_separator, ',');
+
+        let result = increment_lenient("1 234", 1).unwrap();
+        assert_eq!(result.result, "1 235");
+        assert_eq!(result.detected_separator, ' ');
+
+        let result = increment_lenient("1_234", 1).unwrap();
+        assert_eq!(result.result, "1_235");
+        assert_eq!(result.detected_separator, '_');
+    }
+
+    #[test]
+    fn test_increment_lenient_still_rejects_mixed_separators() {
+        // Comma and underscore both present: genuinely ambiguous grouping,
+        // not just a differently-configured separator.
+        assert_eq!(increment_lenient("1,234_567", 1), None);
+    }
+
+    #[test]
+    fn test_analyze_number() {
+        let info = analyze_number("0x00ff").unwrap();
+        assert_eq!(info.base, 16);
+        assert_eq!(info.prefix, "0x");
+        assert_eq!(info.width, 4);
+        assert_eq!(info.value, 0xff);
+
+        let info = analyze_number("1_000").unwrap();
+        assert_eq!(info.base, 10);
+        assert_eq!(info.prefix, "");
+        assert_eq!(info.value, 1000);
+        assert_eq!(info.separator_rtl_indexes, vec![3]);
+
+        assert_eq!(analyze_number("_9"), None);
+        assert_eq!(analyze_number(""), None);
+    }
+
+    #[test]
+    fn test_increment_series() {
+        // A 0..4 series over "00" with base_amount 1 and step 1: each cursor
+        // lands on base_amount + step*index, width preserved.
+        let expected = ["01", "02", "03", "04"];
+        for (index, expected) in expected.into_iter().enumerate() {
+            assert_eq!(
+                increment_series("00", 1, 1, index as u32).unwrap(),
+                expected
+            );
+        }
+
+        // A non-unit step skips values, same as a plain `increment` would.
+        assert_eq!(increment_series("00", 0, 5, 2).unwrap(), "10");
+        // index 0 with step 0 is just a plain increment by base_amount.
+        assert_eq!(increment_series("00", 3, 0, 0).unwrap(), "03");
+    }
+
+    #[test]
+    fn test_find_number_span() {
+        let span = find_number_span("qty=5 addr=0x1f").unwrap();
+        assert_eq!(span.range, 4..5);
+        assert_eq!(span.base, 10);
+        assert!(!span.had_prefix);
+
+        let span = find_number_span("addr=0x1f qty=5").unwrap();
+        assert_eq!(span.range, 5..9);
+        assert_eq!(span.base, 16);
+        assert!(span.had_prefix);
+
+        assert_eq!(find_number_span("no numbers here"), None);
+
+        // Two candidate numbers: the leftmost match wins even when a later
+        // one would also be valid.
+        let span = find_number_span("12 34").unwrap();
+        assert_eq!(span.range, 0..2);
+
+        // `-2` immediately after another number: the leftmost scan position
+        // (the first `2`, unsigned) wins over reading `-2` as its own
+        // signed token.
+        let span = find_number_span("1-2").unwrap();
+        assert_eq!(span.range, 0..1);
+    }
 }
@@ -0,0 +1,78 @@
+use chrono::{NaiveDate, Weekday};
+
+/// Returns the number of ISO 8601 weeks in `year` (52 or 53): a year has 53
+/// when 1 January falls on a Thursday, or on a Wednesday in a leap year.
+fn iso_weeks_in_year(year: i32) -> i64 {
+    let is_leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+    let jan1 = NaiveDate::from_ymd_opt(year, 1, 1)
+        .expect("year is in range")
+        .weekday();
+    if jan1 == Weekday::Thu || (is_leap && jan1 == Weekday::Wed) {
+        53
+    } else {
+        52
+    }
+}
+
+/// Increments an ISO week literal like `2024-W03`, rolling weeks 1-52/53 and
+/// carrying into the year. Width/zero-padding is preserved. An out-of-range
+/// week in the *input* (e.g. `W54` in a year that only has 52 weeks) is
+/// rejected as invalid, the same way [`super::integer::increment`] rejects a
+/// malformed literal rather than silently clamping it.
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    let (year_str, week_str) = selected_text.split_once("-W")?;
+    if year_str.len() != 4 || week_str.len() != 2 {
+        return None;
+    }
+
+    let year: i32 = year_str.parse().ok()?;
+    let week: i64 = week_str.parse().ok()?;
+    if week < 1 || week > iso_weeks_in_year(year) {
+        return None;
+    }
+
+    let mut year = i64::from(year);
+    let mut week = week + amount;
+    loop {
+        if week < 1 {
+            year -= 1;
+            week += iso_weeks_in_year(year as i32);
+        } else if week > iso_weeks_in_year(year as i32) {
+            week -= iso_weeks_in_year(year as i32);
+            year += 1;
+        } else {
+            break;
+        }
+    }
+
+    Some(format!("{year:04}-W{week:02}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_iso_week() {
+        let tests = [
+            ("2024-W03", 1, "2024-W04"),
+            ("2024-W04", -1, "2024-W03"),
+            ("2024-W01", -1, "2023-W52"),
+            // 2020 is a 53-week ISO year.
+            ("2020-W53", 1, "2021-W01"),
+            ("2021-W01", -1, "2020-W53"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_increment_iso_week_rejects_invalid_input() {
+        // 2024 only has 52 ISO weeks.
+        assert_eq!(increment("2024-W54", 1), None);
+        assert_eq!(increment("2024-W00", 1), None);
+        assert_eq!(increment("not-a-week", 1), None);
+    }
+}
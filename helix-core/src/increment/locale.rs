@@ -0,0 +1,166 @@
+/// A set of month/weekday names used by [`super::increment_locale`] to
+/// render a result instead of the English names `chrono`'s own
+/// `%a`/`%A`/`%b`/`%B` formatting would produce. Weekday arrays are in
+/// Monday-first order, matching `chrono::Weekday::num_days_from_monday`.
+pub(crate) struct Locale {
+    pub tag: &'static str,
+    pub months_short: [&'static str; 12],
+    pub months_long: [&'static str; 12],
+    pub weekdays_short: [&'static str; 7],
+    pub weekdays_long: [&'static str; 7],
+}
+
+// Adding a language is just another row here; `locale_for` and
+// `increment_locale` need no changes.
+pub(crate) static LOCALES: &[Locale] = &[
+    Locale {
+        tag: "en",
+        months_short: [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ],
+        months_long: [
+            "January",
+            "February",
+            "March",
+            "April",
+            "May",
+            "June",
+            "July",
+            "August",
+            "September",
+            "October",
+            "November",
+            "December",
+        ],
+        weekdays_short: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        weekdays_long: [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+    },
+    Locale {
+        tag: "de",
+        months_short: [
+            "Jan", "Feb", "Mär", "Apr", "Mai", "Jun", "Jul", "Aug", "Sep", "Okt", "Nov", "Dez",
+        ],
+        months_long: [
+            "Januar",
+            "Februar",
+            "März",
+            "April",
+            "Mai",
+            "Juni",
+            "Juli",
+            "August",
+            "September",
+            "Oktober",
+            "November",
+            "Dezember",
+        ],
+        weekdays_short: ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+        weekdays_long: [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+    },
+    Locale {
+        tag: "fr",
+        months_short: [
+            "janv.", "févr.", "mars", "avr.", "mai", "juin", "juil.", "août", "sept.", "oct.",
+            "nov.", "déc.",
+        ],
+        months_long: [
+            "janvier",
+            "février",
+            "mars",
+            "avril",
+            "mai",
+            "juin",
+            "juillet",
+            "août",
+            "septembre",
+            "octobre",
+            "novembre",
+            "décembre",
+        ],
+        weekdays_short: ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+        weekdays_long: [
+            "lundi",
+            "mardi",
+            "mercredi",
+            "jeudi",
+            "vendredi",
+            "samedi",
+            "dimanche",
+        ],
+    },
+    Locale {
+        tag: "es",
+        months_short: [
+            "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sep", "oct", "nov", "dic",
+        ],
+        months_long: [
+            "enero",
+            "febrero",
+            "marzo",
+            "abril",
+            "mayo",
+            "junio",
+            "julio",
+            "agosto",
+            "septiembre",
+            "octubre",
+            "noviembre",
+            "diciembre",
+        ],
+        weekdays_short: ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        weekdays_long: [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+    },
+];
+
+/// Looks up a locale by its primary language subtag (`"de-DE"` and `"de_AT"`
+/// both resolve to `"de"`), falling back to English for anything
+/// unrecognized rather than failing outright.
+pub(crate) fn locale_for(tag: &str) -> &'static Locale {
+    let primary = tag.split(['-', '_']).next().unwrap_or(tag);
+    LOCALES
+        .iter()
+        .find(|locale| locale.tag.eq_ignore_ascii_case(primary))
+        .unwrap_or(&LOCALES[0])
+}
+
+/// Best-effort locale detection from the environment, checked in the same
+/// order `setlocale(LC_TIME, "")` would consult. Returns the lowercased
+/// language part of the first variable set to something other than `C` or
+/// `POSIX`, even one [`LOCALES`] has no row for (which [`locale_for`] then
+/// maps to English); `"en"` if none is set.
+pub(crate) fn detect_locale() -> String {
+    for var in ["LC_ALL", "LC_TIME", "LANG"] {
+        if let Ok(value) = std::env::var(var) {
+            let primary = value.split(['-', '_', '.']).next().unwrap_or("");
+            if !primary.is_empty() && !primary.eq_ignore_ascii_case("C") && !primary.eq_ignore_ascii_case("POSIX")
+            {
+                return primary.to_lowercase();
+            }
+        }
+    }
+    "en".to_string()
+}
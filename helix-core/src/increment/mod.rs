@@ -1,10 +1,560 @@
+// This module intentionally avoids `log::warn!`/`log::info!` in the hot
+// path: multi-cursor increments call these functions once per selection,
+// and per-call logging at anything above `trace!` floods the editor log
+// with what is normal, successful operation.
+mod cycle;
 mod date_time;
+mod epoch;
+mod escape;
+mod fiscal_quarter;
+mod float;
+mod hex_float;
 mod integer;
+mod isoweek;
+mod locale;
+mod percent_escape;
+mod roman;
+mod scientific;
+
+pub use epoch::EpochOptions;
+pub use float::Precision;
+pub use hex_float::HexFloatField;
+pub use integer::{
+    default_prefix_rules, AlignedIncrement, Base, BitRangeEndpoint, BitRangeMode, ClampedIncrement,
+    HexPrefixStyle, LenientIncrement, NumberInfo, NumberSpan, PrefixRule,
+};
+
+/// Wrapper pairs that [integer] will strip from the selection, increment the
+/// inside, and restore, when the whole selection (quotes/brackets included)
+/// was selected, e.g. `"42"` or `[42]`. Only a single matching pair is
+/// stripped.
+const WRAPPERS: [(char, char); 6] = [
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+];
 
 pub fn integer(selected_text: &str, amount: i64) -> Option<String> {
-    integer::increment(selected_text, amount)
+    if let Some(result) = integer::increment(selected_text, amount) {
+        return Some(result);
+    }
+
+    let mut chars = selected_text.chars();
+    let first = chars.next()?;
+    let last = chars.next_back()?;
+    let (open, close) = WRAPPERS
+        .iter()
+        .find(|&&(open, close)| open == first && close == last)?;
+
+    let inner = &selected_text[open.len_utf8()..selected_text.len() - close.len_utf8()];
+    let incremented = integer::increment(inner, amount)?;
+    Some(format!("{open}{incremented}{close}"))
+}
+
+/// Like [`integer`], but detecting `rules` instead of the built-in base
+/// prefix table, for number literal styles this crate doesn't know about
+/// (e.g. a `%` binary prefix or `&H` hex prefix from some assembler
+/// dialect). See [`PrefixRule`].
+pub fn integer_with_prefixes(
+    selected_text: &str,
+    amount: i64,
+    rules: &[PrefixRule],
+) -> Option<String> {
+    if let Some(result) = integer::increment_with_prefixes(selected_text, amount, rules) {
+        return Some(result);
+    }
+
+    let mut chars = selected_text.chars();
+    let first = chars.next()?;
+    let last = chars.next_back()?;
+    let (open, close) = WRAPPERS
+        .iter()
+        .find(|&&(open, close)| open == first && close == last)?;
+
+    let inner = &selected_text[open.len_utf8()..selected_text.len() - close.len_utf8()];
+    let incremented = integer::increment_with_prefixes(inner, amount, rules)?;
+    Some(format!("{open}{incremented}{close}"))
+}
+
+/// Like [`integer`], but if `grow_separators` is `false`, skips inserting a
+/// new separator when the result grows past its current grouping, leaving
+/// existing separators in place instead (e.g. `999_999 + 1` -> `1000_000`
+/// rather than `1_000_000`). Lets users who hand-group their numbers keep
+/// full control over where separators land. [`integer`] behaves as if this
+/// were `true`.
+pub fn integer_with_separator_growth(
+    selected_text: &str,
+    amount: i64,
+    grow_separators: bool,
+) -> Option<String> {
+    if let Some(result) =
+        integer::increment_with_separator_growth(selected_text, amount, grow_separators)
+    {
+        return Some(result);
+    }
+
+    let mut chars = selected_text.chars();
+    let first = chars.next()?;
+    let last = chars.next_back()?;
+    let (open, close) = WRAPPERS
+        .iter()
+        .find(|&&(open, close)| open == first && close == last)?;
+
+    let inner = &selected_text[open.len_utf8()..selected_text.len() - close.len_utf8()];
+    let incremented =
+        integer::increment_with_separator_growth(inner, amount, grow_separators)?;
+    Some(format!("{open}{incremented}{close}"))
+}
+
+/// Like [`integer`], but if `cap` is given, clamps `amount` into that range
+/// before applying it and reports whether clamping occurred - a guard
+/// against a fat-fingered repeat count turning into a confusingly enormous
+/// jump. `cap: None` preserves [`integer`]'s behavior exactly.
+pub fn integer_clamped(
+    selected_text: &str,
+    amount: i64,
+    cap: Option<std::ops::RangeInclusive<i64>>,
+) -> Option<ClampedIncrement> {
+    integer::increment_clamped(selected_text, amount, cap)
+}
+
+/// Like [`integer`], but reports which thousands separator character was
+/// actually used - useful for pasted numbers whose separator doesn't match
+/// this editor's own default (`,` instead of `_`, or vice versa).
+pub fn integer_lenient(selected_text: &str, amount: i64) -> Option<LenientIncrement> {
+    integer::increment_lenient(selected_text, amount)
+}
+
+/// Like [`integer`], but for a number right-aligned in a fixed-width column
+/// (leading spaces, e.g. a row of a table with right-aligned values): keeps
+/// the selection's total width constant by trimming/adding leading spaces
+/// rather than letting the field grow or shrink, so the rest of the column
+/// stays aligned. Space-padding only - distinct from zero-padding width
+/// preservation, which [`integer`] already does on its own.
+pub fn integer_aligned(selected_text: &str, amount: i64) -> Option<AlignedIncrement> {
+    integer::increment_aligned(selected_text, amount)
+}
+
+/// Detects the base/prefix/width of a number literal without modifying it.
+pub fn analyze_number(selected_text: &str) -> Option<NumberInfo> {
+    integer::analyze_number(selected_text)
+}
+
+/// Like [`integer`], but if `grouping` is given, regroups the result's
+/// digits every `grouping` digits, even if the input had no separators.
+/// "Increment and also format" in one step, e.g. `1234567` -> `1_234_567`.
+pub fn integer_with_grouping(
+    selected_text: &str,
+    amount: i64,
+    grouping: Option<usize>,
+) -> Option<String> {
+    integer::increment_with_grouping(selected_text, amount, grouping)
+}
+
+/// Increments only the leading numeric token of `selected_text`, preserving
+/// everything from the first whitespace onward untouched - e.g. a trailing
+/// comment or unit (`"42 // count"` + 1 -> `"43 // count"`).
+pub fn integer_with_trailing_text(selected_text: &str, amount: i64) -> Option<String> {
+    integer::increment_with_trailing_text(selected_text, amount)
+}
+
+/// Flips a single bit (0-based, from the least-significant bit) of a
+/// `0b`/`0x` literal, preserving width/case/prefix.
+pub fn toggle_bit(selected_text: &str, bit_index: u32) -> Option<String> {
+    integer::toggle_bit(selected_text, bit_index)
+}
+
+/// Rotates a fixed-width `0b`/`0x` literal's bits left (positive `amount`)
+/// or right (negative), preserving width/case/prefix - register/mask
+/// editing's analogue of [integer]'s arithmetic increment.
+pub fn rotate(selected_text: &str, amount: i64) -> Option<String> {
+    integer::rotate(selected_text, amount)
+}
+
+/// Increments an HDL-style bit-range slice (`[msb:lsb]`, e.g. `[7:0]`,
+/// `[15:8]`), as used to index a bus/register field. `mode` selects whether
+/// both endpoints shift together (a window move) or only one does
+/// (narrowing/widening the range).
+pub fn bit_range(selected_text: &str, amount: i64, mode: BitRangeMode) -> Option<String> {
+    integer::increment_bit_range(selected_text, amount, mode)
+}
+
+/// Like [integer], but the amount added is `base_amount + step * index`
+/// rather than a single fixed amount - what a multi-cursor "fill a series"
+/// command needs to give each cursor a different value from one entry
+/// point, so the base/width preservation logic only has to be gotten right
+/// once instead of per-cursor. `index` is typically the cursor's position
+/// within the series, starting at 0.
+pub fn integer_series(selected_text: &str, base_amount: i64, step: i64, index: u32) -> Option<String> {
+    integer::increment_series(selected_text, base_amount, step, index)
+}
+
+/// Like [integer], but snaps to the next/previous multiple of `multiple`
+/// in the direction of `amount` rather than adding `amount` directly.
+/// Works on integers and decimals, preserving a trailing unit suffix
+/// (`px`, `em`, `%`, ...) and the decimal's precision. Useful for
+/// layout/CSS edits that should stay on a grid.
+pub fn integer_snapped(selected_text: &str, amount: i64, multiple: i64) -> Option<String> {
+    integer::increment_snapped(selected_text, amount, multiple)
+}
+
+/// Like [integer], but reads a fixed-width `0b`/`0x` literal as two's
+/// complement and wraps at both ends of its width (`0xFF + 1` wraps to
+/// `0x00`, `0x00 - 1` wraps to `0xFF`) instead of clamping at zero. An
+/// explicit mode rather than something [integer] falls back to, since a
+/// bare hex/binary literal is ambiguous between an unsigned register and a
+/// signed fixed-width value.
+pub fn signed_fixed_width(selected_text: &str, amount: i64) -> Option<String> {
+    integer::increment_signed_fixed_width(selected_text, amount)
+}
+
+/// Increments a plain decimal literal like `3.14`, `5.`, or `.5` by `amount`
+/// units of its own last fractional digit, preserving a trailing-dot or
+/// leading-dot shorthand and the fractional width. Distinct from
+/// [integer_snapped], which snaps to a grid instead of adding directly.
+pub fn float(selected_text: &str, amount: i64) -> Option<String> {
+    float::increment(selected_text, amount)
+}
+
+/// Like [`float`], but allows a thousands-style separator in the integer
+/// part (e.g. `"1,000.000"`), regrouping it if the result carries into a
+/// new group. A separator found in the fractional part instead makes the
+/// whole literal invalid (`"1.00,0"` is rejected), since fractional digits
+/// are never grouped.
+pub fn float_with_grouping(selected_text: &str, amount: i64) -> Option<String> {
+    float::increment_grouped(selected_text, amount)
+}
+
+/// Moves a plain decimal literal's decimal point by `places` - positive
+/// shifts it right, negative shifts it left - padding with zeros as needed
+/// and preserving the sign. A pure unit-conversion reformatting rather than
+/// arithmetic: distinct from [`float`]'s digit-by-digit increment.
+pub fn shift_decimal(selected_text: &str, places: i32) -> Option<String> {
+    float::shift_decimal(selected_text, places)
+}
+
+/// Like [`float`], but `precision` controls how the result's fractional
+/// digits are derived from the incremented value, instead of always
+/// preserving the input's own decimal-place count. See [`Precision`].
+pub fn float_with_precision(
+    selected_text: &str,
+    amount: i64,
+    precision: Precision,
+) -> Option<String> {
+    float::increment_with_precision(selected_text, amount, precision)
+}
+
+/// Increments a decimal scientific-notation literal like `1.23e4` or
+/// `6.02e-23` by `amount` units of the mantissa's smallest digit,
+/// renormalizing into the exponent if the mantissa carries out of its
+/// conventional `[1, 10)` range - e.g. `9.99e0` + 1 hundredth -> `1.00e1`,
+/// not `10.00e0`.
+pub fn scientific(selected_text: &str, amount: i64) -> Option<String> {
+    scientific::increment(selected_text, amount)
+}
+
+/// Like [`scientific`], but `precision` controls how many digits the
+/// renormalized mantissa keeps instead of always preserving the input's own
+/// significant-digit count. See [`Precision`].
+pub fn scientific_with_precision(
+    selected_text: &str,
+    amount: i64,
+    precision: Precision,
+) -> Option<String> {
+    scientific::increment_with_precision(selected_text, amount, precision)
+}
+
+/// Increments a single hex escape sequence, `\xHH` or `\u{...}`, preserving
+/// its syntax and digit case. `\xHH` wraps at the 8-bit boundary; `\u{...}`
+/// clamps at the valid code point range and steps over the surrogate range
+/// instead of landing inside it.
+pub fn escape(selected_text: &str, amount: i64) -> Option<String> {
+    escape::increment(selected_text, amount)
+}
+
+/// Increments a single percent-/equals-encoded byte, `%HH` (URL
+/// percent-encoding) or `=HH` (MIME quoted-printable), preserving the sigil
+/// and digit case. Wraps at the 8-bit boundary like [`escape`]'s `\xHH`.
+pub fn percent_escape(selected_text: &str, amount: i64) -> Option<String> {
+    percent_escape::increment(selected_text, amount)
+}
+
+/// Increments one part of a C99 hex float literal like `0x1.8p3`, selected
+/// via `field` since a hex float has two numbers (the mantissa and the
+/// exponent) a caller could mean to bump.
+pub fn hex_float(selected_text: &str, amount: i64, field: HexFloatField) -> Option<String> {
+    hex_float::increment(selected_text, amount, field)
+}
+
+/// Converts the selected number to another base without incrementing it.
+pub fn convert_base(selected_text: &str, to: Base) -> Option<String> {
+    integer::convert_base(selected_text, to)
+}
+
+/// Like [integer], but rendering the result in `to_base` instead of the
+/// input's own base, e.g. incrementing a decimal literal while writing the
+/// result out in hex. Width and separator grouping are not preserved, since
+/// the input and output bases can differ.
+pub fn integer_convert(selected_text: &str, amount: i64, to_base: Base) -> Option<String> {
+    integer::increment_convert(selected_text, amount, to_base)
+}
+
+/// Re-emits a `0x`- or Verilog `'h`-prefixed hex literal in `style`, without
+/// changing the value, e.g. normalizing a file's hex literals to one
+/// notation.
+pub fn normalize_hex_prefix(text: &str, style: HexPrefixStyle) -> Option<String> {
+    integer::normalize_hex_prefix(text, style)
+}
+
+/// Increments a `0b` binary literal, then pads its width up to a natural
+/// register size (the next power of two from 8 bits, or a given multiple),
+/// without ever shrinking an already-wider result. Useful when editing
+/// register masks, where `0b101 + 1` should land at `0b00000110` rather
+/// than the bare `0b110` [`integer`] would otherwise produce.
+pub fn binary_padded(selected_text: &str, amount: i64, multiple_of: Option<usize>) -> Option<String> {
+    integer::increment_binary_padded(selected_text, amount, multiple_of)
+}
+
+/// Expands a 3- or 4-digit octal permission mode (`0o644`, `4755`) into its
+/// symbolic form (`rw-r--r--`), including the setuid/setgid/sticky bits
+/// carried by a leading 4th digit. Meant to pair with incrementing an octal
+/// mode, so a caller can show what the new value actually grants.
+pub fn octal_mode_symbolic(text: &str) -> Option<String> {
+    integer::octal_mode_symbolic(text)
+}
+
+/// Increments an Ada/VHDL based literal (`base#digits#`, e.g. `16#FF#`,
+/// `2#1010#`), a notation [`integer`] doesn't otherwise recognize since the
+/// radix is spelled out as a decimal number rather than a fixed prefix
+/// symbol. Width and digit case are preserved. `None` for a malformed
+/// literal or a radix outside `2..=16`, VHDL's own valid range.
+pub fn based_literal(selected_text: &str, amount: i64) -> Option<String> {
+    integer::increment_based_literal(selected_text, amount)
+}
+
+/// Finds the first number literal in `text` (leftmost wins when there's
+/// more than one candidate) without incrementing it, e.g. for a "select
+/// nearest number" command that should snap the selection onto a literal
+/// before the user decides whether to increment it.
+pub fn integer_span(text: &str) -> Option<NumberSpan> {
+    integer::find_number_span(text)
+}
+
+/// Increments every numeric token found in `text` by `amount`, each in
+/// whatever base it was detected as, e.g. incrementing
+/// `"qty=5 addr=0x1f"` by 1 yields `"qty=6 addr=0x20"`. Returns the
+/// rewritten text and the byte ranges of the tokens that changed, or
+/// `None` if `text` contained no number. For "bump this one number", use
+/// [integer] instead.
+pub fn integer_all(text: &str, amount: i64) -> Option<(String, Vec<std::ops::Range<usize>>)> {
+    integer::increment_all(text, amount)
+}
+
+/// Increments a bare Unix epoch timestamp by `amount` seconds. This is an
+/// explicit mode (a distinct action), not something [integer] auto-detects,
+/// since a bare integer is otherwise ambiguous.
+pub fn epoch(selected_text: &str, amount: i64, opts: EpochOptions) -> Option<String> {
+    epoch::increment(selected_text, amount, opts)
+}
+
+/// Increments an ISO week literal like `2024-W03`, carrying into the year.
+pub fn iso_week(selected_text: &str, amount: i64) -> Option<String> {
+    isoweek::increment(selected_text, amount)
+}
+
+/// Increments a fiscal-quarter literal like `FY24Q3`, carrying into the
+/// fiscal year.
+pub fn fiscal_quarter(selected_text: &str, amount: i64) -> Option<String> {
+    fiscal_quarter::increment(selected_text, amount)
+}
+
+/// Increments a standalone roman numeral selection like `IV`, e.g. for a
+/// `Chapter IV` heading. An explicit mode like [epoch] or [iso_week]:
+/// [integer_all]'s mixed-text scan never reaches for this, since a roman
+/// numeral's letters (`I`, `MIX`, `DID`, ...) overlap with ordinary
+/// English words too much to guess at safely. Only meant to be wired up to
+/// a command that runs on a selection the user made on purpose. See
+/// [`roman`] for the full heuristic.
+pub fn roman_numeral(selected_text: &str, amount: i64) -> Option<String> {
+    roman::increment(selected_text, amount)
+}
+
+/// Moves `amount` steps (wrapping) through `words`, starting wherever
+/// `selected_text` matches one of them, preserving its case pattern.
+/// Generalizes a boolean toggle to an arbitrary ordered set, e.g. cycling
+/// `debug`/`info`/`warn`/`error` or `TODO`/`FIXME`/`DONE`.
+pub fn cycle(selected_text: &str, amount: i64, words: &[&str]) -> Option<String> {
+    cycle::cycle(selected_text, amount, words)
 }
 
 pub fn date_time(selected_text: &str, amount: i64) -> Option<String> {
     date_time::increment(selected_text, amount)
 }
+
+/// Like [`date_time`], but for a two-digit-year date like `01/02/24`
+/// described by a small `"MM/DD/YY"`-style field-order/separator
+/// descriptor, century-rolling the year through a configurable `pivot`
+/// (years below `pivot` read as `20xx`, at or above as `19xx`) instead of
+/// chrono's own fixed `%y` pivot.
+pub fn date_time_two_digit_year(
+    selected_text: &str,
+    amount: i64,
+    format: &str,
+    pivot: u32,
+) -> Option<String> {
+    date_time::increment_two_digit_year(selected_text, amount, format, pivot)
+}
+
+/// Like [`date_time`], but for the written-month-name formats (`Nov 24,
+/// 2021` and friends), rendering the result with `locale`'s month/weekday
+/// names (e.g. `"de"`, `"fr"`, `"es"`) instead of English. Pass `None` for
+/// `locale` to fall back to a best-effort guess from the environment's
+/// `LC_ALL`/`LC_TIME`/`LANG`.
+pub fn date_time_locale(selected_text: &str, amount: i64, locale: Option<&str>) -> Option<String> {
+    match locale {
+        Some(locale) => date_time::increment_locale(selected_text, amount, locale),
+        None => date_time::increment_locale(selected_text, amount, &date_time::detect_locale()),
+    }
+}
+
+/// Increments a SQL-style quoted date/timestamp literal: a leading type
+/// keyword (`DATE '2024-01-31'`, `TIMESTAMP '...'`) or a trailing cast
+/// (`'2024-01-31'::date`, `'...'::timestamp`) wrapped around a quoted
+/// value, checked case-insensitively. Strips the recognized wrapper,
+/// increments the quoted contents via [`date_time`], and restores the
+/// wrapper unchanged. Falls back to [`integer_all`]'s general number scan
+/// when the contents aren't a date this module recognizes, so a plain
+/// quoted id (`'42'::bigint`) still increments sensibly. `None` if there's
+/// no recognized wrapper, the quotes don't match, or nothing inside
+/// increments.
+pub fn sql_quoted(selected_text: &str, amount: i64) -> Option<String> {
+    const KEYWORDS: [&str; 2] = ["date", "timestamp"];
+    const CAST_SUFFIXES: [&str; 2] = ["::date", "::timestamp"];
+
+    let lower = selected_text.to_ascii_lowercase();
+
+    let mut prefix_end = 0;
+    for keyword in KEYWORDS {
+        if let Some(rest) = lower.strip_prefix(keyword) {
+            let ws_len = rest.len() - rest.trim_start().len();
+            if ws_len > 0 {
+                prefix_end = keyword.len() + ws_len;
+                break;
+            }
+        }
+    }
+
+    let mut suffix_start = selected_text.len();
+    for suffix in CAST_SUFFIXES {
+        if lower.ends_with(suffix) {
+            suffix_start = selected_text.len() - suffix.len();
+            break;
+        }
+    }
+    // Any other `::type` cast right after the closing quote, e.g. `::bigint`.
+    if suffix_start == selected_text.len() {
+        if let Some(quote) = selected_text.rfind("'::") {
+            let type_name = &selected_text[quote + 3..];
+            if !type_name.is_empty()
+                && type_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+            {
+                suffix_start = quote + 1;
+            }
+        }
+    }
+
+    // Neither a type keyword nor a cast: not a recognized wrapper.
+    if prefix_end == 0 && suffix_start == selected_text.len() {
+        return None;
+    }
+    if prefix_end >= suffix_start {
+        return None;
+    }
+
+    let prefix = &selected_text[..prefix_end];
+    let quoted = &selected_text[prefix_end..suffix_start];
+    let cast_suffix = &selected_text[suffix_start..];
+
+    let inner = quoted.strip_prefix('\'').and_then(|s| s.strip_suffix('\''))?;
+    if inner.is_empty() {
+        return None;
+    }
+
+    let incremented = date_time(inner, amount)
+        .or_else(|| integer_all(inner, amount).map(|(text, _)| text))?;
+
+    Some(format!("{prefix}'{incremented}'{cast_suffix}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_integer_strips_surrounding_wrappers() {
+        let tests = [
+            ("\"42\"", 1, "\"43\""),
+            ("[42]", 1, "[43]"),
+            ("(42)", -1, "(41)"),
+            ("{42}", 1, "{43}"),
+            ("'42'", 1, "'43'"),
+            ("`42`", 1, "`43`"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(integer(original, amount).unwrap(), expected);
+        }
+
+        // Mismatched wrappers aren't stripped.
+        assert_eq!(integer("[42)", 1), None);
+        // A bare number still increments without needing the wrapper path.
+        assert_eq!(integer("42", 1).unwrap(), "43");
+    }
+
+    #[test]
+    fn test_integer_series_over_a_0_to_4_range() {
+        let expected = ["00", "01", "02", "03"];
+        for (index, expected) in expected.into_iter().enumerate() {
+            assert_eq!(
+                integer_series("00", 0, 1, index as u32).unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_sql_quoted_keyword_and_cast_forms() {
+        assert_eq!(
+            sql_quoted("DATE '2024-01-31'", 1).unwrap(),
+            "DATE '2024-02-01'"
+        );
+        assert_eq!(
+            sql_quoted("'2024-01-31'::date", 1).unwrap(),
+            "'2024-02-01'::date"
+        );
+        // Case-insensitive keyword/cast matching.
+        assert_eq!(
+            sql_quoted("date '2024-01-31'", 1).unwrap(),
+            "date '2024-02-01'"
+        );
+        assert_eq!(
+            sql_quoted("'2024-01-31'::DATE", 1).unwrap(),
+            "'2024-02-01'::DATE"
+        );
+    }
+
+    #[test]
+    fn test_sql_quoted_falls_back_to_number_finder_for_non_dates() {
+        assert_eq!(sql_quoted("'42'::bigint", 1).unwrap(), "'43'::bigint");
+    }
+
+    #[test]
+    fn test_sql_quoted_rejects_unrecognized_wrappers() {
+        assert_eq!(sql_quoted("'2024-01-31'", 1), None);
+        assert_eq!(sql_quoted("DATE 2024-01-31", 1), None);
+        assert_eq!(sql_quoted("''::date", 1), None);
+    }
+}
@@ -0,0 +1,56 @@
+//! Increments a single percent-/equals-encoded byte - `%HH` (URL
+//! percent-encoding) or `=HH` (MIME quoted-printable) - by shifting its
+//! value, preserving the sigil and the digit case. Wraps at the 8-bit
+//! boundary (`%FF` + 1 -> `%00`), the same convention [`super::escape`]'s
+//! `\xHH` uses.
+
+/// Increments `%HH` or `=HH` in `selected_text` by `amount`. Returns `None`
+/// for anything else, including a malformed sequence (wrong digit count or
+/// a non-hex-digit character).
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    let sigil = selected_text.chars().next()?;
+    if sigil != '%' && sigil != '=' {
+        return None;
+    }
+    let hex = &selected_text[sigil.len_utf8()..];
+    if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+
+    let value = u8::from_str_radix(hex, 16).ok()?;
+    let new_value = (value as i64 + amount).rem_euclid(256) as u32;
+    Some(format!("{sigil}{}", super::escape::render_hex(hex, new_value, 2)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_percent_encoded_byte() {
+        assert_eq!(increment("%1b", 1).unwrap(), "%1c");
+        assert_eq!(increment("%FF", 1).unwrap(), "%00");
+        assert_eq!(increment("%00", -1).unwrap(), "%ff");
+    }
+
+    #[test]
+    fn test_increment_quoted_printable_byte() {
+        assert_eq!(increment("=1B", 1).unwrap(), "=1C");
+        assert_eq!(increment("=ff", 1).unwrap(), "=00");
+    }
+
+    #[test]
+    fn test_increment_preserves_digit_case() {
+        assert_eq!(increment("%0A", 1).unwrap(), "%0B");
+        assert_eq!(increment("%0a", 1).unwrap(), "%0b");
+    }
+
+    #[test]
+    fn test_increment_rejects_malformed_sequences() {
+        assert_eq!(increment("%ZZ", 1), None);
+        assert_eq!(increment("%1", 1), None);
+        assert_eq!(increment("%123", 1), None);
+        assert_eq!(increment("1B", 1), None);
+        assert_eq!(increment("\\x1b", 1), None);
+    }
+}
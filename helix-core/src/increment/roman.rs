@@ -0,0 +1,141 @@
+//! Roman numeral increment is an explicit, opt-in mode: unlike
+//! [`super::integer::increment_all`]'s mixed-text scan, this module is
+//! never used to auto-detect a token in prose. A roman numeral shares its
+//! alphabet with ordinary words (`I`, `MIX`, `DID`, `LID` are all spelled
+//! with numeral letters and common English besides), so there is no safe way to tell
+//! "the word I" from "the numeral I" without knowing the author's intent.
+//! Instead, [`increment`] only accepts a selection that is, in its
+//! entirety, a canonical roman numeral - the caller (a dedicated command,
+//! not the general increment dispatch) is what supplies that intent by
+//! requiring the user to select the token explicitly.
+
+const VALUES: [(u32, &str); 13] = [
+    (1000, "M"),
+    (900, "CM"),
+    (500, "D"),
+    (400, "CD"),
+    (100, "C"),
+    (90, "XC"),
+    (50, "L"),
+    (40, "XL"),
+    (10, "X"),
+    (9, "IX"),
+    (5, "V"),
+    (4, "IV"),
+    (1, "I"),
+];
+
+const MAX_VALUE: u32 = 3999;
+
+/// Parses a canonical (strictly subtractive, no repeated-more-than-thrice)
+/// roman numeral, uppercase only. Returns `None` for anything that isn't
+/// exactly one, including loose historical forms like `IIII`: accepting
+/// only the one canonical rendering per value means [`render`] round-trips
+/// every input this accepts, which is what lets [`increment`] tell a real
+/// numeral apart from an incidental word.
+fn parse(text: &str) -> Option<u32> {
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut value = 0;
+    let mut rest = text;
+    for &(digit_value, symbol) in &VALUES {
+        while let Some(stripped) = rest.strip_prefix(symbol) {
+            value += digit_value;
+            rest = stripped;
+        }
+    }
+
+    if !rest.is_empty() || value == 0 || value > MAX_VALUE {
+        return None;
+    }
+
+    // Reject non-canonical forms that still happened to fully consume
+    // (greedy stripping above never over-matches, but confirm round-trip
+    // anyway so this stays correct if VALUES is ever edited).
+    if render(value) != text {
+        return None;
+    }
+
+    Some(value)
+}
+
+/// Renders `value` (`1..=3999`) as a canonical uppercase roman numeral.
+fn render(mut value: u32) -> String {
+    let mut result = String::new();
+    for &(digit_value, symbol) in &VALUES {
+        while value >= digit_value {
+            result.push_str(symbol);
+            value -= digit_value;
+        }
+    }
+    result
+}
+
+/// Increments a standalone roman numeral selection like `IV` by `amount`,
+/// e.g. `Chapter IV` selected as just `IV` + 1 -> `V`. Lowercase input
+/// (`iv`) is accepted and the case is preserved on output; mixed case is
+/// rejected as not a numeral. `None` if the selection isn't exactly a
+/// canonical roman numeral (see the module docs) or the result would fall
+/// outside `I..=MMMCMXCIX` (1..=3999).
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    let is_lower = selected_text.chars().all(|c| c.is_ascii_lowercase());
+    let is_upper = selected_text.chars().all(|c| c.is_ascii_uppercase());
+    if !is_lower && !is_upper {
+        return None;
+    }
+    let upper = selected_text.to_ascii_uppercase();
+
+    let value = parse(&upper)?;
+    let new_value = value as i64 + amount;
+    if new_value < 1 || new_value > MAX_VALUE as i64 {
+        return None;
+    }
+
+    let rendered = render(new_value as u32);
+    Some(if is_lower {
+        rendered.to_ascii_lowercase()
+    } else {
+        rendered
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_roman_numeral() {
+        let tests = [
+            ("IV", 1, "V"),
+            ("III", 1, "IV"),
+            ("IX", 1, "X"),
+            ("MCMXCIX", 1, "MM"),
+            ("iv", 1, "v"),
+            ("V", -1, "IV"),
+        ];
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).as_deref(), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_increment_roman_numeral_rejects_out_of_range() {
+        assert_eq!(increment("I", -1), None);
+        assert_eq!(increment("MMMCMXCIX", 1), None);
+    }
+
+    #[test]
+    fn test_increment_roman_numeral_rejects_non_canonical_or_non_numerals() {
+        // Common English words made mostly or entirely of roman-numeral
+        // letters must not be treated as numerals.
+        assert_eq!(increment("DIME", 1), None);
+        assert_eq!(increment("LID", 1), None);
+        // Loose/non-canonical forms are rejected, not normalized.
+        assert_eq!(increment("IIII", 1), None);
+        assert_eq!(increment("VX", 1), None);
+        assert_eq!(increment("", 1), None);
+        assert_eq!(increment("Iv", 1), None);
+    }
+}
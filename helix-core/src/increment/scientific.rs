@@ -0,0 +1,165 @@
+//! Increment for decimal scientific-notation literals (`1.23e4`,
+//! `6.02e-23`): a mantissa in the same shape [`super::float`] understands,
+//! followed by an `e`/`E`-introduced decimal exponent. Unlike a plain
+//! decimal, a carry or borrow that pushes the mantissa out of its
+//! conventional `[1, 10)` range has to be renormalized into the exponent
+//! (`9.99e0` + 1 hundredth -> `1.00e1`, not `10.00e0`), since leaving it
+//! un-renormalized would misrepresent how many digits are actually
+//! significant.
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::float::Precision;
+
+static SCIENTIFIC: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(-)?([0-9]+)(?:\.([0-9]*))?([eE])([+-]?[0-9]+)$").unwrap());
+
+/// Increments `selected_text` by `amount` units of the mantissa's smallest
+/// digit, preserving the input's own significant-digit count (equivalent to
+/// [`increment_with_precision`] with [`Precision::Auto`]).
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    increment_with_precision(selected_text, amount, Precision::Auto)
+}
+
+/// Like [`increment`], but `precision` controls how many digits the
+/// renormalized mantissa keeps instead of always preserving the input's own
+/// significant-digit count. Returns `None` for anything that isn't a
+/// mantissa-and-exponent literal.
+pub fn increment_with_precision(
+    selected_text: &str,
+    amount: i64,
+    precision: Precision,
+) -> Option<String> {
+    let captures = SCIENTIFIC.captures(selected_text)?;
+    let sign = captures.get(1).map_or("", |m| m.as_str());
+    let int_digits = captures.get(2).unwrap().as_str();
+    let frac_digits = captures.get(3).map_or("", |m| m.as_str());
+    let e = captures.get(4).unwrap().as_str();
+    let exponent_text = captures.get(5).unwrap().as_str();
+    let exponent: i64 = exponent_text.parse().ok()?;
+    let exponent_has_plus = exponent_text.starts_with('+');
+
+    let decimals = frac_digits.len() as u32;
+    let scale = 10i128.checked_pow(decimals)?;
+    let mantissa: f64 = format!("{sign}{int_digits}.{frac_digits}").parse().ok()?;
+    let scaled = (mantissa * scale as f64).round() as i128;
+    let new_scaled = scaled.checked_add(amount as i128)?;
+
+    let is_negative = new_scaled < 0;
+    let abs_digits = format!(
+        "{:01$}",
+        new_scaled.unsigned_abs(),
+        decimals as usize + 1
+    );
+    let point_index = abs_digits.len() - decimals as usize;
+
+    // Renormalize so exactly one nonzero digit sits left of the point,
+    // folding however many places that took into the exponent.
+    let (new_exponent, normalized_digits) = match abs_digits.find(|c: char| c != '0') {
+        None => (exponent, "0".to_string()),
+        Some(first_nonzero) => (
+            exponent + (point_index as i64 - first_nonzero as i64 - 1),
+            abs_digits[first_nonzero..].to_string(),
+        ),
+    };
+
+    let normalized_mantissa: f64 = format!(
+        "{}.{}",
+        &normalized_digits[..1],
+        normalized_digits.get(1..).unwrap_or("")
+    )
+    .parse()
+    .ok()?;
+    let signed_mantissa = if is_negative {
+        -normalized_mantissa
+    } else {
+        normalized_mantissa
+    };
+
+    let output_decimals = match precision {
+        Precision::DecimalPlaces(n) => n,
+        Precision::SignificantDigits(n) => n.saturating_sub(1),
+        // The input's own significant-digit count, less the one digit that
+        // sits left of the renormalized point - for an already-normalized
+        // input like `1.23e0` that is just its decimal-place count. An
+        // all-zero mantissa has no significant digits and keeps its
+        // decimal-place count instead.
+        Precision::Auto => {
+            let significant = format!("{int_digits}{frac_digits}")
+                .trim_start_matches('0')
+                .len() as u32;
+            if significant == 0 {
+                decimals
+            } else {
+                significant - 1
+            }
+        }
+    };
+
+    let rendered_mantissa = format!("{:.*}", output_decimals as usize, signed_mantissa);
+    let rendered_exponent = if new_exponent >= 0 && exponent_has_plus {
+        format!("+{new_exponent}")
+    } else {
+        format!("{new_exponent}")
+    };
+
+    Some(format!("{rendered_mantissa}{e}{rendered_exponent}"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_preserves_significant_digits_by_default() {
+        assert_eq!(increment("1.23e0", 1).unwrap(), "1.24e0");
+        assert_eq!(increment("6.02e-23", 1).unwrap(), "6.03e-23");
+    }
+
+    #[test]
+    fn test_increment_renormalizes_a_mantissa_carry_into_the_exponent() {
+        // Same decimal-place count (and so the same significant-digit
+        // count) as the input, but the decimal point moves once the
+        // mantissa carries past 10.
+        assert_eq!(increment("9.99e0", 1).unwrap(), "1.00e1");
+        assert_eq!(increment("9.99e3", 1).unwrap(), "1.00e4");
+    }
+
+    #[test]
+    fn test_increment_renormalizes_a_mantissa_borrow_below_one() {
+        assert_eq!(increment("1.00e5", -1).unwrap(), "9.90e4");
+    }
+
+    #[test]
+    fn test_increment_decimal_places_vs_significant_digits_on_the_same_input() {
+        // Fixed decimal places keeps reporting the same number of digits
+        // after the point even once the carry adds a digit of magnitude...
+        assert_eq!(
+            increment_with_precision("9.99e0", 1, Precision::DecimalPlaces(2)).unwrap(),
+            "1.00e1"
+        );
+        // ...while significant digits keeps the total digit count fixed
+        // instead, shrinking the decimal places by one to compensate.
+        assert_eq!(
+            increment_with_precision("9.99e0", 1, Precision::SignificantDigits(3)).unwrap(),
+            "1.00e1"
+        );
+        assert_eq!(
+            increment_with_precision("9.99e0", 1, Precision::SignificantDigits(4)).unwrap(),
+            "1.000e1"
+        );
+    }
+
+    #[test]
+    fn test_increment_keeps_explicit_exponent_sign() {
+        assert_eq!(increment("1.5e+2", 1).unwrap(), "1.6e+2");
+    }
+
+    #[test]
+    fn test_increment_rejects_non_scientific_literals() {
+        assert_eq!(increment("1.23", 1), None);
+        assert_eq!(increment("5", 1), None);
+        assert_eq!(increment("e5", 1), None);
+    }
+}
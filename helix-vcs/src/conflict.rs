@@ -0,0 +1,241 @@
+//! Parses and resolves standard `<<<<<<<`/`=======`/`>>>>>>>` merge-conflict
+//! markers, the same ones [`crate::git`]'s `has_conflict_markers` merely
+//! detects. Both git's two-way (no common-ancestor section) and diff3-style
+//! three-way (an added `|||||||` base section) conflict regions are
+//! understood, since either can show up in a working tree depending on the
+//! VCS's merge-conflict style setting.
+
+/// Which side of a conflict region [`resolve_conflicts`] should keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    /// The `<<<<<<<` section - the local/current branch's version.
+    Ours,
+    /// The `>>>>>>>` section - the incoming/merged branch's version.
+    Theirs,
+    /// The `|||||||` section - the common ancestor's version. Only present
+    /// in a diff3-style (three-way) conflict region.
+    Base,
+}
+
+/// One `<<<<<<< ... >>>>>>>` conflict region found in a file, as byte ranges
+/// into the original `contents` (each range excludes its own marker line).
+struct ConflictRegion {
+    /// The whole region, from the `<<<<<<<` line through the `>>>>>>>` line,
+    /// inclusive - what gets replaced by the chosen side.
+    whole: std::ops::Range<usize>,
+    ours: std::ops::Range<usize>,
+    /// The `|||||||` section, if this is a diff3-style three-way region.
+    base: Option<std::ops::Range<usize>>,
+    theirs: std::ops::Range<usize>,
+}
+
+/// Removes every conflict marker in `contents` and keeps only `side`'s text
+/// throughout, returning the cleaned bytes. A two-way region (no `|||||||`
+/// base section) has no text to keep for [`Side::Base`]; that region is left
+/// untouched rather than silently dropping it, the same as a malformed
+/// region (missing or out-of-order markers). Returns `contents` unchanged if
+/// it has no conflict markers at all.
+pub fn resolve_conflicts(contents: &[u8], side: Side) -> Vec<u8> {
+    let regions = find_conflict_regions(contents);
+    if regions.is_empty() {
+        return contents.to_vec();
+    }
+
+    let mut resolved = Vec::with_capacity(contents.len());
+    let mut cursor = 0;
+    for region in &regions {
+        let kept = match side {
+            Side::Ours => Some(&region.ours),
+            Side::Theirs => Some(&region.theirs),
+            Side::Base => region.base.as_ref(),
+        };
+        let Some(kept) = kept else {
+            // No section to keep for this side (a two-way region has no
+            // base): leave the whole region untouched.
+            continue;
+        };
+
+        resolved.extend_from_slice(&contents[cursor..region.whole.start]);
+        resolved.extend_from_slice(&contents[kept.clone()]);
+        cursor = region.whole.end;
+    }
+    resolved.extend_from_slice(&contents[cursor..]);
+
+    resolved
+}
+
+/// Tracks how far into a conflict region the scan in [`find_conflict_regions`]
+/// currently is, carrying the byte offsets collected so far.
+enum State {
+    Outside,
+    Ours {
+        whole_start: usize,
+        ours_start: usize,
+    },
+    Base {
+        whole_start: usize,
+        ours_start: usize,
+        ours_end: usize,
+        base_start: usize,
+    },
+    Theirs {
+        whole_start: usize,
+        ours_start: usize,
+        ours_end: usize,
+        base: Option<(usize, usize)>,
+        theirs_start: usize,
+    },
+}
+
+/// Scans `contents` for well-formed conflict regions, skipping over anything
+/// that doesn't follow the expected `<<<<<<<` [`|||||||`] `=======`
+/// `>>>>>>>` marker order rather than guessing at a malformed one.
+fn find_conflict_regions(contents: &[u8]) -> Vec<ConflictRegion> {
+    let mut regions = Vec::new();
+    let mut state = State::Outside;
+    let mut pos = 0;
+
+    for line in contents.split_inclusive(|&b| b == b'\n') {
+        let line_start = pos;
+        let next_start = pos + line.len();
+        pos = next_start;
+
+        let is_ours_marker = line.starts_with(b"<<<<<<<");
+        let is_base_marker = line.starts_with(b"|||||||");
+        let is_sep_marker = line.starts_with(b"=======");
+        let is_theirs_marker = line.starts_with(b">>>>>>>");
+
+        state = match (state, is_ours_marker, is_base_marker, is_sep_marker, is_theirs_marker) {
+            // A new marker while one is already open is malformed; restart
+            // the search from here rather than nesting.
+            (_, true, _, _, _) => State::Ours {
+                whole_start: line_start,
+                ours_start: next_start,
+            },
+            (
+                State::Ours {
+                    whole_start,
+                    ours_start,
+                },
+                _,
+                true,
+                _,
+                _,
+            ) => State::Base {
+                whole_start,
+                ours_start,
+                ours_end: line_start,
+                base_start: next_start,
+            },
+            (
+                State::Ours {
+                    whole_start,
+                    ours_start,
+                },
+                _,
+                _,
+                true,
+                _,
+            ) => State::Theirs {
+                whole_start,
+                ours_start,
+                ours_end: line_start,
+                base: None,
+                theirs_start: next_start,
+            },
+            (
+                State::Base {
+                    whole_start,
+                    ours_start,
+                    ours_end,
+                    base_start,
+                },
+                _,
+                _,
+                true,
+                _,
+            ) => State::Theirs {
+                whole_start,
+                ours_start,
+                ours_end,
+                base: Some((base_start, line_start)),
+                theirs_start: next_start,
+            },
+            (
+                State::Theirs {
+                    whole_start,
+                    ours_start,
+                    ours_end,
+                    base,
+                    theirs_start,
+                },
+                _,
+                _,
+                _,
+                true,
+            ) => {
+                regions.push(ConflictRegion {
+                    whole: whole_start..next_start,
+                    ours: ours_start..ours_end,
+                    base: base.map(|(start, end)| start..end),
+                    theirs: theirs_start..line_start,
+                });
+                State::Outside
+            }
+            (state, ..) => state,
+        };
+    }
+
+    regions
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_resolve_conflicts_two_way() {
+        let contents = b"a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\nb\n";
+
+        assert_eq!(resolve_conflicts(contents, Side::Ours), b"a\nours\nb\n");
+        assert_eq!(resolve_conflicts(contents, Side::Theirs), b"a\ntheirs\nb\n");
+        // No base section to keep: a two-way region is left untouched.
+        assert_eq!(resolve_conflicts(contents, Side::Base), contents);
+    }
+
+    #[test]
+    fn test_resolve_conflicts_three_way() {
+        let contents =
+            b"a\n<<<<<<< HEAD\nours\n||||||| base\nancestor\n=======\ntheirs\n>>>>>>> branch\nb\n";
+
+        assert_eq!(resolve_conflicts(contents, Side::Ours), b"a\nours\nb\n");
+        assert_eq!(resolve_conflicts(contents, Side::Theirs), b"a\ntheirs\nb\n");
+        assert_eq!(resolve_conflicts(contents, Side::Base), b"a\nancestor\nb\n");
+    }
+
+    #[test]
+    fn test_resolve_conflicts_multiple_regions_in_one_file() {
+        let contents = b"<<<<<<< HEAD\n1\n=======\n2\n>>>>>>> b\nmid\n<<<<<<< HEAD\n3\n=======\n4\n>>>>>>> b\n";
+        assert_eq!(
+            resolve_conflicts(contents, Side::Ours),
+            b"1\nmid\n3\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn test_resolve_conflicts_leaves_malformed_regions_untouched() {
+        // No closing marker at all.
+        let contents = b"a\n<<<<<<< HEAD\nours\n=======\ntheirs\nb\n";
+        assert_eq!(resolve_conflicts(contents, Side::Ours), contents.to_vec());
+
+        // `=======` with nothing opened before it.
+        let contents = b"a\n=======\nb\n>>>>>>> branch\nc\n";
+        assert_eq!(resolve_conflicts(contents, Side::Ours), contents.to_vec());
+    }
+
+    #[test]
+    fn test_resolve_conflicts_returns_input_unchanged_without_markers() {
+        let contents = b"plain file\nwith no conflicts\n";
+        assert_eq!(resolve_conflicts(contents, Side::Ours), contents.to_vec());
+    }
+}
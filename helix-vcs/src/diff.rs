@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::ops::Range;
 use std::sync::Arc;
 
@@ -10,6 +11,7 @@
 use tokio::time::Instant;
 
 use crate::diff::worker::DiffWorker;
+use line_cache::InternedRopeLines;
 
 mod line_cache;
 mod worker;
@@ -159,6 +161,30 @@ pub fn is_pure_removal(&self) -> bool {
     }
 }
 
+/// Returns the first hunk in `hunks` (sorted in ascending order, as
+/// [`Diff`]'s are) that starts after `line`. If the cursor is inside a hunk,
+/// that hunk is skipped in favor of the next one. With `wrap` set, falls
+/// back to the first hunk in the list when there's no hunk after `line`.
+/// This is the same search `Diff::next_hunk` does, exposed for callers that
+/// only have a plain hunk list (e.g. a goto-next-change command working off
+/// a snapshot) so they don't have to reimplement it.
+pub fn next_hunk_after(hunks: &[Hunk], line: u32, wrap: bool) -> Option<&Hunk> {
+    hunks
+        .iter()
+        .find(|hunk| hunk.after.start > line)
+        .or_else(|| wrap.then(|| hunks.first()).flatten())
+}
+
+/// Like [next_hunk_after], but searches backwards for the last hunk that
+/// ends at or before `line`.
+pub fn prev_hunk_before(hunks: &[Hunk], line: u32, wrap: bool) -> Option<&Hunk> {
+    hunks
+        .iter()
+        .rev()
+        .find(|hunk| hunk.after.end <= line)
+        .or_else(|| wrap.then(|| hunks.last()).flatten())
+}
+
 /// A list of changes in a file sorted in ascending
 /// non-overlapping order
 #[derive(Debug)]
@@ -290,3 +316,140 @@ pub fn hunk_at(&self, line: u32, include_removal: bool) -> Option<u32> {
         }
     }
 }
+
+/// Diffs `diff_base` against `doc` synchronously and returns the resulting
+/// hunks, without spinning up a [`DiffHandle`]'s background worker. Intended
+/// for one-shot callers (like [`ChangedLines::for_document`]) that just want
+/// a snapshot and don't need the incremental updates a `DiffHandle` provides.
+/// Returns an empty list rather than partial hunks if either text is too
+/// large to diff, the same safety cutoff the worker applies.
+fn compute_hunks(diff_base: &Rope, doc: &Rope) -> Vec<Hunk> {
+    let interner = InternedRopeLines::new(diff_base.clone(), doc.clone());
+    let mut hunks = Vec::new();
+    if let Some(input) = interner.interned_lines() {
+        imara_diff::diff(ALGORITHM, input, |before: Range<u32>, after: Range<u32>| {
+            hunks.push(Hunk { before, after })
+        });
+    }
+    hunks
+}
+
+/// Per-line classification of a diff against a base, flattened from
+/// [`Hunk`]s so a minimalist gutter doesn't have to re-derive this from the
+/// hunk list itself. Line numbers are `doc`-relative (the `after` side of a
+/// [`Hunk`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    /// Lines that only exist in `doc`, not in the diff base.
+    pub added: HashSet<u32>,
+    /// Lines modified in `doc` relative to the diff base.
+    pub modified: HashSet<u32>,
+    /// Lines a deletion sits "between": a pure removal has an empty `after`
+    /// range (no line of `doc` was added or changed), so there's no line to
+    /// mark as modified - instead the line immediately following the
+    /// removal is recorded here, the same place a gutter draws the deletion
+    /// marker.
+    pub removed_at: HashSet<u32>,
+}
+
+impl ChangedLines {
+    fn from_hunks(hunks: &[Hunk]) -> ChangedLines {
+        let mut changed = ChangedLines::default();
+        for hunk in hunks {
+            if hunk.is_pure_removal() {
+                changed.removed_at.insert(hunk.after.start);
+            } else if hunk.is_pure_insertion() {
+                changed.added.extend(hunk.after.clone());
+            } else {
+                changed.modified.extend(hunk.after.clone());
+            }
+        }
+        changed
+    }
+
+    /// Diffs `diff_base` against `doc` and flattens the resulting hunks into
+    /// per-line markers. A convenience over hand-rolling [`compute_hunks`]
+    /// and the hunk-kind checks for every gutter that only needs line
+    /// numbers, not the full hunk ranges.
+    pub fn for_document(diff_base: &Rope, doc: &Rope) -> ChangedLines {
+        ChangedLines::from_hunks(&compute_hunks(diff_base, doc))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn hunk(after_start: u32, after_end: u32) -> Hunk {
+        Hunk {
+            before: 0..0,
+            after: after_start..after_end,
+        }
+    }
+
+    #[test]
+    fn test_next_hunk_after() {
+        let hunks = [hunk(1, 2), hunk(5, 7), hunk(10, 11)];
+        assert_eq!(next_hunk_after(&hunks, 0, false), Some(&hunks[0]));
+        // Cursor inside the first hunk: skip to the next one.
+        assert_eq!(next_hunk_after(&hunks, 1, false), Some(&hunks[1]));
+        assert_eq!(next_hunk_after(&hunks, 10, false), None);
+        assert_eq!(next_hunk_after(&hunks, 10, true), Some(&hunks[0]));
+        assert_eq!(next_hunk_after(&[], 0, true), None);
+    }
+
+    #[test]
+    fn test_prev_hunk_before() {
+        let hunks = [hunk(1, 2), hunk(5, 7), hunk(10, 11)];
+        assert_eq!(prev_hunk_before(&hunks, 11, false), Some(&hunks[2]));
+        assert_eq!(prev_hunk_before(&hunks, 5, false), Some(&hunks[0]));
+        assert_eq!(prev_hunk_before(&hunks, 0, false), None);
+        assert_eq!(prev_hunk_before(&hunks, 0, true), Some(&hunks[2]));
+        assert_eq!(prev_hunk_before(&[], 0, true), None);
+    }
+
+    #[test]
+    fn test_changed_lines_added_and_modified() {
+        let base = Rope::from_str("foo\nbar\ntest\nfoo\n");
+        let doc = Rope::from_str("foo\nbar bar\ntest\nfoo\nnew\n");
+        let changed = ChangedLines::for_document(&base, &doc);
+        assert_eq!(changed.modified, HashSet::from([1]));
+        assert_eq!(changed.added, HashSet::from([4]));
+        assert_eq!(changed.removed_at, HashSet::new());
+    }
+
+    #[test]
+    fn test_changed_lines_pure_removal() {
+        let base = Rope::from_str("foo\nfoo bar\nbar\n");
+        let doc = Rope::from_str("foo\nbar\n");
+        let changed = ChangedLines::for_document(&base, &doc);
+        assert!(changed.added.is_empty());
+        assert!(changed.modified.is_empty());
+        // The removed line sat between "foo" (line 0) and "bar" (now line
+        // 1): there's no line of `doc` to mark, so the removal is recorded
+        // at the line it now sits in front of.
+        assert_eq!(changed.removed_at, HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_changed_lines_trailing_newline() {
+        // Adding a trailing newline does not add a new line of `doc` to
+        // diff against - ropey's line iterator still yields two lines for
+        // "foo\nbar\n" - but the last line's token now includes the
+        // newline where it didn't before, so the diff (correctly) still
+        // reports that line as modified rather than unchanged.
+        let base = Rope::from_str("foo\nbar");
+        let doc = Rope::from_str("foo\nbar\n");
+        let changed = ChangedLines::for_document(&base, &doc);
+        assert!(changed.added.is_empty());
+        assert!(changed.removed_at.is_empty());
+        assert_eq!(changed.modified, HashSet::from([1]));
+
+        // Removing the final line entirely (not just its trailing newline)
+        // is a pure removal sitting after the new last line.
+        let base = Rope::from_str("foo\nbar\n");
+        let doc = Rope::from_str("foo\n");
+        let changed = ChangedLines::for_document(&base, &doc);
+        assert_eq!(changed.removed_at, HashSet::from([1]));
+    }
+}
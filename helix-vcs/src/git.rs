@@ -1,21 +1,69 @@
 use anyhow::{bail, Context, Result};
 use arc_swap::ArcSwap;
-use std::path::Path;
-use std::sync::Arc;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 
 use gix::objs::tree::EntryMode;
 use gix::sec::trust::DefaultForLevel;
 use gix::{Commit, ObjectId, Repository, ThreadSafeRepository};
 
-use crate::DiffProvider;
+use crate::{
+    truncate_with_ellipsis, BlameInfo, CancelToken, Cancelled, ChangeCounts, ChangeKind,
+    DiffBaseSource, DiffProvider, FileChange, FileMode, HeadState, ProviderCapabilities, RepoCaps,
+    RepoSnapshot, StatusConfig, TooLarge,
+};
 
 #[cfg(test)]
 mod test;
 
 pub struct Git;
 
+/// Caches an already-discovered [`ThreadSafeRepository`] per query path, so
+/// repeated operations on the same file (diffing on every keystroke, status
+/// polling, ...) don't re-walk the filesystem looking for `.git` each time.
+/// Keyed by the canonicalized path [`Git::open_repo`] was asked to discover
+/// from, since every call site in this file passes the same `ceiling_dir`
+/// (`None`).
+fn repo_cache() -> &'static Mutex<HashMap<PathBuf, ThreadSafeRepository>> {
+    static REPO_CACHE: OnceLock<Mutex<HashMap<PathBuf, ThreadSafeRepository>>> = OnceLock::new();
+    REPO_CACHE.get_or_init(Default::default)
+}
+
+/// Drops cached [`ThreadSafeRepository`] handles so the next git operation
+/// reopens the repository from disk instead of reusing a stale one.
+/// `Some(root)` drops only entries whose cached lookup path falls under
+/// `root` (matching either the exact path an embedder queried, or the
+/// repository's top-level root); `None` drops everything.
+///
+/// This crate has no filesystem watcher of its own to invalidate the cache
+/// automatically - every cached handle is kept until something calls this
+/// function, or, in the editor itself, until whatever change-detection the
+/// UI layer has in place calls it on the embedder's behalf. An embedder that
+/// commits, checks out a branch, or otherwise changes repository state
+/// through a separate process (rather than through this crate) needs to call
+/// this explicitly, or a later diff/status/blame call may keep returning
+/// results against the pre-change state.
+pub fn invalidate_repo_cache(root: Option<&Path>) {
+    let mut cache = repo_cache().lock();
+    match root {
+        None => cache.clear(),
+        Some(root) => {
+            let root = root.canonicalize().unwrap_or_else(|_| root.to_owned());
+            cache.retain(|cached_path, _| !cached_path.starts_with(&root));
+        }
+    }
+}
+
 impl Git {
     fn open_repo(path: &Path, ceiling_dir: Option<&Path>) -> Result<ThreadSafeRepository> {
+        let cache_key = path.canonicalize().unwrap_or_else(|_| path.to_owned());
+        if let Some(repo) = repo_cache().lock().get(&cache_key) {
+            return Ok(repo.clone());
+        }
+
         // custom open options
         let mut git_open_opts_map = gix::sec::trust::Mapping::<gix::open::Options>::default();
 
@@ -57,6 +105,7 @@ fn open_repo(path: &Path, ceiling_dir: Option<&Path>) -> Result<ThreadSafeReposi
             git_open_opts_map,
         )?;
 
+        repo_cache().lock().insert(cache_key, res.clone());
         Ok(res)
     }
 }
@@ -66,17 +115,37 @@ fn get_diff_base(&self, file: &Path) -> Result<Vec<u8>> {
         debug_assert!(!file.exists() || file.is_file());
         debug_assert!(file.is_absolute());
 
-        // TODO cache repository lookup
-
         let repo_dir = file.parent().context("file has no parent directory")?;
         let repo = Git::open_repo(repo_dir, None)
             .context("failed to open git repo")?
             .to_thread_local();
         let head = repo.head_commit()?;
-        let file_oid = find_file_in_commit(&repo, &head, file)?;
+        let file_oid = match find_file_in_commit(&repo, &head, file)? {
+            Some(oid) => oid,
+            None => match find_renamed_source(&repo, &head, file) {
+                Some(oid) => oid,
+                // Untracked at HEAD and no rename match: the whole file is
+                // new, so diff against nothing rather than erroring (matches
+                // `get_diff_base_public`'s handling of the same situation).
+                None => return Ok(Vec::new()),
+            },
+        };
 
         let file_object = repo.find_object(file_oid)?;
         let mut data = file_object.detach().data;
+
+        // gix has no verified header-only size lookup in the version this
+        // crate depends on, so this checks after decompression rather than
+        // before, same as the `hg` backend in `hg.rs` - it still avoids the CRLF
+        // normalization pass below on an oversized blob, just not the
+        // initial object read.
+        if let Some(limit) = self.max_diff_base_size() {
+            let size = data.len() as u64;
+            if size > limit {
+                return Err(TooLarge { size, limit }.into());
+            }
+        }
+
         // convert LF to CRLF if configured to avoid showing every line as changed
         if repo
             .config_snapshot()
@@ -102,6 +171,17 @@ fn get_diff_base(&self, file: &Path) -> Result<Vec<u8>> {
     }
 
     fn get_current_head_name(&self, file: &Path) -> Result<Arc<ArcSwap<Box<str>>>> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let name = head_name_from_repo(&repo)?;
+        Ok(Arc::new(ArcSwap::from_pointee(name.into_boxed_str())))
+    }
+
+    fn head_state(&self, file: &Path) -> Result<HeadState> {
         debug_assert!(!file.exists() || file.is_file());
         debug_assert!(file.is_absolute());
         let repo_dir = file.parent().context("file has no parent directory")?;
@@ -109,31 +189,1354 @@ fn get_current_head_name(&self, file: &Path) -> Result<Arc<ArcSwap<Box<str>>>> {
             .context("failed to open git repo")?
             .to_thread_local();
         let head_ref = repo.head_ref()?;
-        let head_commit = repo.head_commit()?;
 
-        let name = match head_ref {
-            Some(reference) => reference.name().shorten().to_string(),
-            None => head_commit.id.to_hex_with_len(8).to_string(),
+        match (head_ref, repo.head_commit()) {
+            // A named branch with a resolvable commit.
+            (Some(reference), Ok(_)) => {
+                Ok(HeadState::Named(reference.name().shorten().to_string()))
+            }
+            // A named branch (HEAD is a symbolic ref to it), but the ref
+            // doesn't point at a commit yet - a freshly initialized repo, or
+            // one just switched to a brand-new branch before its first
+            // commit. Not an error: there's simply nothing to diff yet.
+            (Some(reference), Err(_)) => {
+                Ok(HeadState::Unborn(reference.name().shorten().to_string()))
+            }
+            // HEAD points directly at a commit rather than a branch.
+            (None, Ok(commit)) => Ok(HeadState::Detached(commit.id.to_hex_with_len(8).to_string())),
+            // Neither a branch nor a resolvable commit: a genuinely broken
+            // or corrupt HEAD, which is an error.
+            (None, Err(err)) => Err(err).context("HEAD does not point to a branch or a commit"),
+        }
+    }
+
+    fn head_extra(&self, file: &Path, key: &str) -> Result<Option<String>> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let head = repo.head_commit()?;
+
+        let Some(note) = read_git_note(&repo, head.id) else {
+            return Ok(None);
         };
+        Ok(find_trailer(note.as_bytes(), key))
+    }
 
-        Ok(Arc::new(ArcSwap::from_pointee(name.into_boxed_str())))
+    fn for_each_changed_file(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        f: &mut dyn FnMut(Result<FileChange>),
+    ) -> Result<()> {
+        let repo = Git::open_repo(cwd, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        for_each_changed_file_impl(&repo, config, f, &mut |_scanned| {}, &CancelToken::none())
+    }
+
+    fn for_each_changed_file_with_progress(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        f: &mut dyn FnMut(Result<FileChange>),
+        progress: &mut dyn FnMut(usize),
+    ) -> Result<()> {
+        let repo = Git::open_repo(cwd, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        for_each_changed_file_impl(&repo, config, f, progress, &CancelToken::none())
+    }
+
+    /// Like [`Self::for_each_changed_file`], but checks `cancel` at the same
+    /// cadence the walk already checks progress, so a caller can drop a
+    /// stale request without waiting for a huge repo's walk to finish.
+    fn for_each_changed_file_cancellable(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        f: &mut dyn FnMut(Result<FileChange>),
+        cancel: &CancelToken,
+    ) -> Result<()> {
+        if cancel.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        let repo = Git::open_repo(cwd, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        for_each_changed_file_impl(&repo, config, f, &mut |_scanned| {}, cancel)
+    }
+
+    fn repo_capabilities(&self, file: &Path) -> Result<RepoCaps> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+
+        let writable = match repo.work_dir() {
+            Some(work_dir) => std::fs::metadata(work_dir)
+                .map(|metadata| !metadata.permissions().readonly())
+                .unwrap_or(false),
+            None => false,
+        };
+
+        Ok(RepoCaps {
+            has_work_dir: repo.work_dir().is_some(),
+            writable,
+            provider: self.name(),
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            blame: true,
+            history: true,
+            staged_diff: true,
+        }
+    }
+
+    fn merge_base(&self, file: &Path, rev_a: &str, rev_b: &str) -> Result<String> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+
+        let a = resolve_rev(&repo, rev_a)
+            .with_context(|| format!("failed to resolve revision {rev_a}"))?;
+        let b = resolve_rev(&repo, rev_b)
+            .with_context(|| format!("failed to resolve revision {rev_b}"))?;
+        let base = merge_base(&repo, a, b)
+            .with_context(|| format!("no common ancestor between {rev_a} and {rev_b}"))?;
+        Ok(base.to_string())
+    }
+
+    fn get_diff_base_rev(&self, file: &Path, rev: &str) -> Result<Vec<u8>> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let work_dir = repo.work_dir().context("repo has no worktree")?;
+        let rel_path = file.strip_prefix(work_dir)?;
+
+        let commit_id =
+            resolve_rev(&repo, rev).with_context(|| format!("failed to resolve revision {rev}"))?;
+        let commit_data = repo.find_object(commit_id)?.detach().data;
+        let tree_id = commit_tree_id(&commit_data).context("revision has no tree")?;
+        let mut tracked = HashMap::new();
+        walk_tree(&repo, tree_id, &mut PathBuf::new(), &mut tracked)?;
+        let file_oid = tracked
+            .get(rel_path)
+            .context("file is untracked at this revision")?;
+        Ok(repo.find_object(*file_oid)?.detach().data)
+    }
+
+    fn get_diff_base_ancestor(&self, file: &Path, n: u32) -> Result<Vec<u8>> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let work_dir = repo.work_dir().context("repo has no worktree")?;
+        let rel_path = file.strip_prefix(work_dir)?;
+
+        let mut commit_id = repo.head_commit()?.id;
+        for step in 0..n {
+            let commit_data = repo.find_object(commit_id)?.detach().data;
+            commit_id = commit_parent_ids(&commit_data)
+                .into_iter()
+                .next()
+                .with_context(|| format!("no ancestor {} commits back from HEAD", step + 1))?;
+        }
+
+        file_blob_at_commit(&repo, commit_id, rel_path)?
+            .context("file did not exist this many commits back")
+    }
+
+    // Git has no concept of phases (public/draft/secret), so "public" is
+    // approximated as "reachable from some remote-tracking branch": a
+    // commit visible outside this clone is effectively shared and
+    // immutable, the same way hg's public phase marks a changeset pushed
+    // and no longer safe to rewrite.
+    fn get_diff_base_public(&self, file: &Path) -> Result<Vec<u8>> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let head = repo.head_commit()?;
+
+        let Some(public_commit) = nearest_public_ancestor(&repo, head.id) else {
+            // No remote-tracking branch reaches this history at all (no
+            // remote configured, or everything is still unpushed): the
+            // whole file is effectively still draft, so diff against
+            // nothing rather than erroring.
+            return Ok(Vec::new());
+        };
+
+        let work_dir = repo.work_dir().context("repo has no worktree")?;
+        let rel_path = file.strip_prefix(work_dir)?;
+        let commit_data = repo.find_object(public_commit)?.detach().data;
+        let tree_id = commit_tree_id(&commit_data).context("revision has no tree")?;
+        let mut tracked = HashMap::new();
+        walk_tree(&repo, tree_id, &mut PathBuf::new(), &mut tracked)?;
+        match tracked.get(rel_path) {
+            Some(&oid) => Ok(repo.find_object(oid)?.detach().data),
+            // Untracked at the public ancestor: the whole file is new.
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn get_diff_base_source(&self, file: &Path, source: DiffBaseSource) -> Result<Vec<u8>> {
+        if source == DiffBaseSource::Head {
+            return self.get_diff_base(file);
+        }
+
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let work_dir = repo.work_dir().context("repo has no worktree")?;
+        let rel_path = file.strip_prefix(work_dir)?;
+        let indexed = read_index(&repo)?.get(rel_path).map(|entry| entry.oid);
+
+        match (source, indexed) {
+            (DiffBaseSource::Index, Some(oid)) | (DiffBaseSource::Auto, Some(oid)) => {
+                Ok(repo.find_object(oid)?.detach().data)
+            }
+            (DiffBaseSource::Index, None) => bail!("file is not staged in the index"),
+            (DiffBaseSource::Auto, None) => self.get_diff_base(file),
+            (DiffBaseSource::Head, _) => unreachable!("handled above"),
+        }
+    }
+
+    fn changed_files_between(
+        &self,
+        file: &Path,
+        rev_a: &str,
+        rev_b: &str,
+    ) -> Result<Vec<FileChange>> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let work_dir = repo.work_dir().context("repo has no worktree")?.to_owned();
+
+        let a = resolve_rev(&repo, rev_a)
+            .with_context(|| format!("failed to resolve revision {rev_a}"))?;
+        let b = resolve_rev(&repo, rev_b)
+            .with_context(|| format!("failed to resolve revision {rev_b}"))?;
+
+        let mut tracked_a = HashMap::new();
+        let commit_data = repo.find_object(a)?.detach().data;
+        let tree_a = commit_tree_id(&commit_data).context("revision has no tree")?;
+        walk_tree(&repo, tree_a, &mut PathBuf::new(), &mut tracked_a)?;
+
+        let mut tracked_b = HashMap::new();
+        let commit_data = repo.find_object(b)?.detach().data;
+        let tree_b = commit_tree_id(&commit_data).context("revision has no tree")?;
+        walk_tree(&repo, tree_b, &mut PathBuf::new(), &mut tracked_b)?;
+
+        let mut changes = Vec::new();
+        for (path, oid_b) in &tracked_b {
+            let kind = match tracked_a.get(path) {
+                Some(oid_a) if oid_a == oid_b => continue,
+                Some(_) => ChangeKind::Modified,
+                None => ChangeKind::Added,
+            };
+            changes.push(FileChange {
+                abs_path: work_dir.join(path),
+                path: path.clone(),
+                kind,
+                from_path: None,
+            });
+        }
+        for path in tracked_a.keys() {
+            if !tracked_b.contains_key(path) {
+                changes.push(FileChange {
+                    abs_path: work_dir.join(path),
+                    path: path.clone(),
+                    kind: ChangeKind::Deleted,
+                    from_path: None,
+                });
+            }
+        }
+
+        Ok(changes)
+    }
+
+    fn file_mode(&self, file: &Path) -> Result<FileMode> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let work_dir = repo.work_dir().context("repo has no worktree")?;
+        let rel_path = file.strip_prefix(work_dir)?;
+
+        let entry = read_index(&repo)?
+            .get(rel_path)
+            .copied()
+            .context("file is not tracked")?;
+
+        // Standard git mode bits: 0o120000 (symlink), 0o100755/0o100644
+        // (executable/regular file) - see gitformat-index(5).
+        match entry.mode & 0o170000 {
+            0o120000 => Ok(FileMode::Symlink),
+            _ if entry.mode & 0o111 != 0 => Ok(FileMode::Executable),
+            _ => Ok(FileMode::Regular),
+        }
+    }
+
+    /// Content-identity check (same blob in the index): a hardlink, or a
+    /// duplicate that hasn't diverged. Unlike the line-similarity heuristic
+    /// used for rename detection, this isn't fuzzy, so two copies that have
+    /// since drifted even slightly will report `false` rather than
+    /// "probably the same file".
+    fn same_tracked_source(&self, a: &Path, b: &Path) -> Result<bool> {
+        debug_assert!(!a.exists() || a.is_file());
+        debug_assert!(!b.exists() || b.is_file());
+        debug_assert!(a.is_absolute());
+        debug_assert!(b.is_absolute());
+
+        let repo_dir = a.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let work_dir = repo.work_dir().context("repo has no worktree")?;
+
+        let (Ok(rel_a), Ok(rel_b)) = (a.strip_prefix(work_dir), b.strip_prefix(work_dir)) else {
+            // Not in the same repo: can't be the same tracked source.
+            return Ok(false);
+        };
+
+        let index = read_index(&repo)?;
+        let (Some(entry_a), Some(entry_b)) = (index.get(rel_a), index.get(rel_b)) else {
+            return Ok(false);
+        };
+        Ok(entry_a.oid == entry_b.oid)
+    }
+
+    /// Opens the repo once and reuses it for all three pieces, instead of
+    /// the default implementation's one-open-per-method composition.
+    fn repo_snapshot(&self, file: &Path) -> Result<RepoSnapshot> {
+        debug_assert!(!file.exists() || file.is_file());
+        debug_assert!(file.is_absolute());
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+
+        let head_name = head_name_from_repo(&repo)?;
+
+        let mut counts = ChangeCounts::default();
+        let mut file_dirty = false;
+        for_each_changed_file_impl(
+            &repo,
+            &StatusConfig::default(),
+            &mut |change| {
+                if let Ok(change) = change {
+                    if change.abs_path == file {
+                        file_dirty = true;
+                    }
+                    counts.record(change.kind);
+                }
+            },
+            &mut |_scanned| {},
+            &CancelToken::none(),
+        )?;
+
+        Ok(RepoSnapshot {
+            head_name: Arc::new(ArcSwap::from_pointee(head_name.into_boxed_str())),
+            counts,
+            file_dirty,
+        })
+    }
+
+    /// Conflicts can only exist while `MERGE_HEAD` is present, so that's
+    /// checked first rather than walking the worktree unconditionally; once
+    /// a merge is confirmed in progress, this stops at the first file with
+    /// conflict markers instead of collecting the full change set the way
+    /// [`DiffProvider::for_each_changed_file`] does.
+    fn has_conflicts(&self, cwd: &Path) -> Result<bool> {
+        let repo = Git::open_repo(cwd, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+
+        if read_merge_head(&repo).is_none() {
+            return Ok(false);
+        }
+
+        let work_dir = repo.work_dir().context("repo has no worktree")?.to_owned();
+        for entry in ignore::WalkBuilder::new(&work_dir).build() {
+            let Ok(entry) = entry else { continue };
+            if !entry.file_type().map_or(false, |kind| kind.is_file()) {
+                continue;
+            }
+            if std::fs::read(entry.path()).map_or(false, |contents| has_conflict_markers(&contents))
+            {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    fn current_commit_summary(&self, file: &Path, max_len: Option<usize>) -> Result<String> {
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        let head = repo
+            .head_commit()
+            .context("repository has no commits yet")?;
+        let commit_data = repo.find_object(head.id)?.detach().data;
+        let summary = commit_summary(&commit_data).context("commit has no message")?;
+        Ok(truncate_with_ellipsis(&summary, max_len))
+    }
+
+    /// Walks first-parent history from `HEAD`, diffing the file's content one
+    /// commit at a time (see [`diff_line_origins`]) to find which commit
+    /// last introduced each line still present at `HEAD`. Unlike
+    /// [`merge_base`], which walks every parent, this only follows first
+    /// parents, so a line whose "real" introduction lives down a merged-in
+    /// side branch gets attributed to the merge instead - a real but
+    /// approximate blame, not a full any-parent one.
+    fn blame_range(&self, file: &Path, lines: Range<usize>) -> Result<Vec<BlameInfo>> {
+        let (repo, attribution) = blame_attribution(file)?;
+        if lines.end > attribution.len() {
+            bail!(
+                "requested lines {}..{} extend past the file's {} lines",
+                lines.start,
+                lines.end,
+                attribution.len()
+            );
+        }
+        let mut summaries = HashMap::new();
+        Ok(lines
+            .map(|line| blame_info(&repo, line, attribution[line], &mut summaries))
+            .collect())
+    }
+
+    /// Like [`Self::blame_range`], but for a scattered set of lines (e.g.
+    /// just the ones currently visible in a gutter) rather than a
+    /// contiguous range. The history walk - the expensive part of a blame -
+    /// still only runs once per call, shared by every requested line, so
+    /// this is the method to reach for when a caller would otherwise call
+    /// [`Self::blame_range`] once per line. Callers that re-blame the same
+    /// file as a viewport scrolls should still cache the returned map (or
+    /// widen `lines` to the whole file and cache that) keyed by file and
+    /// diff base, since nothing here is cached across calls.
+    fn blame_lines(&self, file: &Path, lines: &[u32]) -> Result<HashMap<u32, BlameInfo>> {
+        let (repo, attribution) = blame_attribution(file)?;
+        let mut summaries = HashMap::new();
+        lines
+            .iter()
+            .map(|&line| {
+                let commit_id = *attribution.get(line as usize).with_context(|| {
+                    format!(
+                        "requested line {line} extends past the file's {} lines",
+                        attribution.len()
+                    )
+                })?;
+                Ok((line, blame_info(&repo, line as usize, commit_id, &mut summaries)))
+            })
+            .collect()
+    }
+
+    fn working_directory(&self, file: &Path) -> Result<PathBuf> {
+        let repo_dir = file.parent().context("file has no parent directory")?;
+        let repo = Git::open_repo(repo_dir, None)
+            .context("failed to open git repo")?
+            .to_thread_local();
+        repo.work_dir()
+            .map(ToOwned::to_owned)
+            .context("repo has no worktree")
+    }
+}
+
+/// How many files to scan between `progress` callback invocations. Calling
+/// it on every file would make the progress reporting itself the dominant
+/// cost of the walk on a huge repo, so it's throttled instead.
+const PROGRESS_THROTTLE: usize = 256;
+
+/// Shared implementation backing [`DiffProvider::for_each_changed_file`],
+/// [`DiffProvider::for_each_changed_file_with_progress`], and
+/// [`DiffProvider::for_each_changed_file_cancellable`]; `progress` is
+/// invoked with the number of files scanned so far, throttled to roughly
+/// every [`PROGRESS_THROTTLE`] files, plus once at the end of the walk.
+/// `cancel` is checked at the same cadence, bailing with [`Cancelled`] if
+/// tripped; callers with no cancellation source pass `&CancelToken::none()`.
+///
+/// `config.list_copies` and `config.check_exec` are accepted but have no
+/// effect yet: this walk only ever compares whole-file contents against the
+/// tracked blob, with no rename/copy correlation or tree-entry mode tracked
+/// alongside it (see [`walk_tree`]) to diff against.
+fn for_each_changed_file_impl(
+    repo: &Repository,
+    config: &StatusConfig,
+    f: &mut dyn FnMut(Result<FileChange>),
+    progress: &mut dyn FnMut(usize),
+    cancel: &CancelToken,
+) -> Result<()> {
+    let work_dir = repo.work_dir().context("repo has no worktree")?.to_owned();
+    let merging = repo.git_dir().join("MERGE_HEAD").exists();
+    let scan_root = match &config.subdir {
+        Some(subdir) => work_dir.join(subdir),
+        None => work_dir.clone(),
+    };
+
+    let mut tracked = HashMap::new();
+    if let Ok(head) = repo.head_commit() {
+        let commit_data = repo.find_object(head.id)?.detach().data;
+        if let Some(tree_id) = commit_tree_id(&commit_data) {
+            walk_tree(&repo, tree_id, &mut PathBuf::new(), &mut tracked)?;
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut scanned = 0;
+    for entry in ignore::WalkBuilder::new(&scan_root).build() {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                f(Err(err.into()));
+                continue;
+            }
+        };
+        if !entry.file_type().map_or(false, |kind| kind.is_file()) {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(rel_path) = path.strip_prefix(&work_dir) else {
+            continue;
+        };
+        let rel_path = rel_path.to_path_buf();
+        seen.insert(rel_path.clone());
+
+        scanned += 1;
+        if scanned % PROGRESS_THROTTLE == 0 {
+            progress(scanned);
+            if cancel.is_cancelled() {
+                return Err(Cancelled.into());
+            }
+        }
+
+        let change = match tracked.get(&rel_path) {
+            Some(&oid) => {
+                let on_disk = std::fs::read(path);
+                let base = repo.find_object(oid).map(|obj| obj.detach().data);
+                match (on_disk, base) {
+                    (Ok(on_disk), Ok(base)) if on_disk != base => {
+                        let kind = if merging && has_conflict_markers(&on_disk) {
+                            ChangeKind::Conflict
+                        } else {
+                            ChangeKind::Modified
+                        };
+                        Some(Ok(FileChange {
+                            path: rel_path,
+                            abs_path: path.to_path_buf(),
+                            kind,
+                            from_path: None,
+                        }))
+                    }
+                    (Ok(_), Ok(_)) if config.list_clean => Some(Ok(FileChange {
+                        path: rel_path,
+                        abs_path: path.to_path_buf(),
+                        kind: ChangeKind::Clean,
+                        from_path: None,
+                    })),
+                    (Ok(_), Ok(_)) => None,
+                    (Err(err), _) => Some(Err(err.into())),
+                    (_, Err(err)) => Some(Err(err.into())),
+                }
+            }
+            None if !config.list_unknown => None,
+            None => {
+                let kind = if merging
+                    && std::fs::read(path)
+                        .map(|contents| has_conflict_markers(&contents))
+                        .unwrap_or(false)
+                {
+                    ChangeKind::Conflict
+                } else {
+                    ChangeKind::Added
+                };
+                Some(Ok(FileChange {
+                    path: rel_path,
+                    abs_path: path.to_path_buf(),
+                    kind,
+                    from_path: None,
+                }))
+            }
+        };
+        if let Some(change) = change {
+            f(change);
+        }
+    }
+
+    if config.list_ignored {
+        // Re-walk with all the standard filters (`.gitignore`, global and
+        // repo excludes) disabled: anything turned up here that the first,
+        // filtered walk didn't already visit was skipped specifically
+        // because it's ignored, which is cheaper than matching every entry
+        // against the ignore rules by hand.
+        for entry in ignore::WalkBuilder::new(&scan_root)
+            .standard_filters(false)
+            .build()
+        {
+            let Ok(entry) = entry else { continue };
+            if !entry.file_type().map_or(false, |kind| kind.is_file()) {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(rel_path) = path.strip_prefix(&work_dir) else {
+                continue;
+            };
+            if seen.contains(rel_path) {
+                continue;
+            }
+            f(Ok(FileChange {
+                path: rel_path.to_path_buf(),
+                abs_path: path.to_path_buf(),
+                kind: ChangeKind::Ignored,
+                from_path: None,
+            }));
+        }
+    }
+
+    for rel_path in tracked.into_keys() {
+        if let Some(subdir) = &config.subdir {
+            if !rel_path.starts_with(subdir) {
+                continue;
+            }
+        }
+        if !seen.contains(&rel_path) {
+            let abs_path = work_dir.join(&rel_path);
+            f(Ok(FileChange {
+                path: rel_path,
+                abs_path,
+                kind: ChangeKind::Deleted,
+                from_path: None,
+            }));
+        }
+    }
+
+    progress(scanned);
+    Ok(())
+}
+
+/// The display name of `repo`'s current head: the branch name if there is
+/// one, else the short commit hash, with a `(MERGE a+b)` suffix appended
+/// mid-merge (two parents, `HEAD` and `MERGE_HEAD`, the same way a two-
+/// parent dirstate would in a VCS that tracks merge parents explicitly).
+fn head_name_from_repo(repo: &Repository) -> Result<String> {
+    let head_ref = repo.head_ref()?;
+    let head_commit = repo.head_commit()?;
+
+    let mut name = match head_ref {
+        Some(reference) => reference.name().shorten().to_string(),
+        None => head_commit.id.to_hex_with_len(8).to_string(),
+    };
+
+    if let Some(merge_head) = read_merge_head(repo) {
+        name = format!(
+            "{name} (MERGE {}+{})",
+            head_commit.id.to_hex_with_len(8),
+            merge_head.to_hex_with_len(8)
+        );
+    }
+
+    Ok(name)
+}
+
+/// Reads the other parent of an in-progress merge from `MERGE_HEAD`, if any.
+fn read_merge_head(repo: &Repository) -> Option<ObjectId> {
+    let contents = std::fs::read_to_string(repo.git_dir().join("MERGE_HEAD")).ok()?;
+    ObjectId::from_hex(contents.trim().as_bytes()).ok()
+}
+
+/// Cheaply detects conflict markers left behind by an unresolved merge, by
+/// looking only for a `<<<<<<< ` line that opens a conflict region (the
+/// `=======`/`>>>>>>>` lines are not checked). Only meaningful while `MERGE_HEAD` exists; git
+/// doesn't track a separate "unsure" status the way some other VCSes do, so
+/// this content scan is the classification signal instead.
+fn has_conflict_markers(contents: &[u8]) -> bool {
+    contents
+        .split(|&b| b == b'\n')
+        .any(|line| line.starts_with(b"<<<<<<< "))
+}
+
+/// Parses the tree object id out of a commit object's raw data
+/// (the `tree <hex-oid>` header line).
+fn commit_tree_id(commit_data: &[u8]) -> Option<ObjectId> {
+    let text = std::str::from_utf8(commit_data).ok()?;
+    let line = text.lines().next()?;
+    ObjectId::from_hex(line.strip_prefix("tree ")?.as_bytes()).ok()
+}
+
+/// Recursively walks a git tree object, collecting the path and blob id of
+/// every file (non-tree, non-symlink) entry.
+fn walk_tree(
+    repo: &Repository,
+    tree_id: ObjectId,
+    prefix: &mut PathBuf,
+    out: &mut HashMap<PathBuf, ObjectId>,
+) -> Result<()> {
+    let data = repo.find_object(tree_id)?.detach().data;
+    let mut i = 0;
+    while i < data.len() {
+        let space = i + data[i..]
+            .iter()
+            .position(|&b| b == b' ')
+            .context("malformed tree entry")?;
+        let mode = std::str::from_utf8(&data[i..space])?;
+        let nul = space
+            + data[space..]
+                .iter()
+                .position(|&b| b == 0)
+                .context("malformed tree entry")?;
+        let name = std::str::from_utf8(&data[space + 1..nul])?;
+        let oid = ObjectId::try_from(&data[nul + 1..nul + 21]).context("malformed tree entry")?;
+        i = nul + 21;
+
+        prefix.push(name);
+        match mode {
+            "40000" => walk_tree(repo, oid, prefix, out)?,
+            "120000" | "160000" => {}
+            _ => {
+                out.insert(prefix.clone(), oid);
+            }
+        }
+        prefix.pop();
+    }
+    Ok(())
+}
+
+/// One [`read_index`] entry: the staged blob id and its mode bits.
+#[derive(Debug, Clone, Copy)]
+struct IndexEntry {
+    oid: ObjectId,
+    /// The raw on-disk mode bits (e.g. `0o100644`, `0o100755`, `0o120000`).
+    mode: u32,
+}
+
+/// Parses the git index (`.git/index`) into a path -> [`IndexEntry`] map, for
+/// [`DiffProvider::get_diff_base_source`]'s [`DiffBaseSource::Index`] and
+/// [`DiffBaseSource::Auto`]. Only each entry's blob id and mode bits are
+/// read (no other stat-cache data, no extensions); index format v4 (path-prefix-
+/// compressed entry names) is deliberately unsupported rather than guessed
+/// at, since there's no way to verify a hand-rolled decompressor here.
+fn read_index(repo: &Repository) -> Result<HashMap<PathBuf, IndexEntry>> {
+    let data =
+        std::fs::read(repo.git_dir().join("index")).context("failed to read git index")?;
+    if data.len() < 12 || &data[..4] != b"DIRC" {
+        bail!("not a git index file");
+    }
+    let version = u32::from_be_bytes(data[4..8].try_into().unwrap());
+    if version == 4 {
+        bail!("git index format version 4 (path-compressed entries) is not supported");
+    }
+    if version != 2 && version != 3 {
+        bail!("unsupported git index format version {version}");
+    }
+    let entry_count = u32::from_be_bytes(data[8..12].try_into().unwrap());
+
+    let mut entries = HashMap::new();
+    let mut i = 12;
+    for _ in 0..entry_count {
+        let entry_start = i;
+        // 10 fixed 4-byte stat fields (ctime, mtime, dev, ino, mode, uid,
+        // gid, size) precede the blob id; only mode (the 7th field) matters
+        // here besides the blob id itself.
+        if data.len() < i + 62 {
+            bail!("truncated index entry");
+        }
+        let mode = u32::from_be_bytes(data[i + 24..i + 28].try_into().unwrap());
+        let oid = ObjectId::try_from(&data[i + 40..i + 60]).context("malformed index entry")?;
+        let flags = u16::from_be_bytes(data[i + 60..i + 62].try_into().unwrap());
+        let mut name_start = i + 62;
+        if version == 3 && flags & 0x4000 != 0 {
+            name_start += 2;
+        }
+        let nul = name_start
+            + data
+                .get(name_start..)
+                .context("truncated index entry")?
+                .iter()
+                .position(|&b| b == 0)
+                .context("malformed index entry")?;
+        let name =
+            std::str::from_utf8(&data[name_start..nul]).context("non-UTF-8 index entry")?;
+        entries.insert(PathBuf::from(name), IndexEntry { oid, mode });
+
+        let entry_len = nul + 1 - entry_start;
+        i = entry_start + ((entry_len + 7) & !7);
+    }
+    Ok(entries)
+}
+
+/// Follows an annotated tag object's `object <hex>` chain down to the commit
+/// it ultimately points at; a no-op for lightweight tags, which already
+/// point directly at a commit.
+fn peel_to_commit(repo: &Repository, mut oid: ObjectId) -> Option<ObjectId> {
+    for _ in 0..10 {
+        let data = repo.find_object(oid).ok()?.detach().data;
+        let text = std::str::from_utf8(&data).ok()?;
+        let line = text.lines().next()?;
+        if line.starts_with("tree ") {
+            return Some(oid);
+        }
+        oid = ObjectId::from_hex(line.strip_prefix("object ")?.as_bytes()).ok()?;
+    }
+    None
+}
+
+/// Parses the `parent <hex>` header lines out of a commit object's raw data.
+fn commit_parent_ids(commit_data: &[u8]) -> Vec<ObjectId> {
+    let Ok(text) = std::str::from_utf8(commit_data) else {
+        return Vec::new();
+    };
+    text.lines()
+        .take_while(|line| !line.is_empty())
+        .filter_map(|line| line.strip_prefix("parent "))
+        .filter_map(|hex| ObjectId::from_hex(hex.as_bytes()).ok())
+        .collect()
+}
+
+/// Resolves a revision string to a commit id: a full hex object id as-is,
+/// `HEAD`, or a branch/tag name read straight off disk (loose files under
+/// `.git/refs/heads`, `remotes` and `tags`, then `packed-refs`). This
+/// only covers the common cases a caller passing a branch/tag name needs,
+/// not the full `git rev-parse` grammar (`~`, `^`, ranges, ...).
+fn resolve_rev(repo: &Repository, rev: &str) -> Option<ObjectId> {
+    if let Ok(oid) = ObjectId::from_hex(rev.as_bytes()) {
+        return Some(oid);
+    }
+    if rev == "HEAD" {
+        return repo.head_commit().ok().map(|commit| commit.id);
+    }
+
+    for kind in ["heads", "remotes", "tags"] {
+        let candidate = repo.git_dir().join("refs").join(kind).join(rev);
+        if let Ok(hex) = std::fs::read_to_string(candidate) {
+            if let Ok(oid) = ObjectId::from_hex(hex.trim().as_bytes()) {
+                return peel_to_commit(repo, oid);
+            }
+        }
+    }
+
+    let packed = std::fs::read_to_string(repo.git_dir().join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| {
+        if line.starts_with('#') || line.starts_with('^') {
+            return None;
+        }
+        let (hex, name) = line.split_once(' ')?;
+        if name != rev && !name.ends_with(&format!("/{rev}")) {
+            return None;
+        }
+        peel_to_commit(repo, ObjectId::from_hex(hex.as_bytes()).ok()?)
+    })
+}
+
+/// Collects the full set of ancestor commit ids reachable from `start`
+/// (inclusive). Bounded by `MAX_VISITED` so a pathological history doesn't
+/// turn into an unbounded traversal.
+fn ancestors(repo: &Repository, start: ObjectId) -> HashSet<ObjectId> {
+    const MAX_VISITED: usize = 10_000;
+
+    let mut visited = HashSet::new();
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(start);
+    while let Some(id) = queue.pop_front() {
+        if !visited.insert(id) || visited.len() > MAX_VISITED {
+            continue;
+        }
+        if let Ok(data) = repo.find_object(id).map(|object| object.detach().data) {
+            queue.extend(commit_parent_ids(&data));
+        }
+    }
+    visited
+}
+
+/// Finds the common ancestor of `a` and `b`, like `git merge-base`: the
+/// first ancestor of `a` encountered while walking `b`'s ancestry
+/// breadth-first. This matches `git merge-base`'s answer for the common
+/// non-criss-cross case this is used for (diffing a branch against a
+/// target), though unlike the real command it doesn't attempt to pick a
+/// "best" base among multiple equally-close candidates.
+fn merge_base(repo: &Repository, a: ObjectId, b: ObjectId) -> Option<ObjectId> {
+    let ancestors_of_a = ancestors(repo, a);
+
+    let mut visited = HashSet::new();
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(b);
+    while let Some(id) = queue.pop_front() {
+        if !visited.insert(id) {
+            continue;
+        }
+        if ancestors_of_a.contains(&id) {
+            return Some(id);
+        }
+        if let Ok(data) = repo.find_object(id).map(|object| object.detach().data) {
+            queue.extend(commit_parent_ids(&data));
+        }
+    }
+    None
+}
+
+/// Recursively collects the commit ids a ref directory's loose refs point
+/// at (e.g. `.git/refs/remotes`, which nests a directory per remote).
+fn collect_loose_refs(dir: &Path, out: &mut Vec<ObjectId>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_loose_refs(&path, out);
+        } else if let Ok(hex) = std::fs::read_to_string(&path) {
+            if let Ok(oid) = ObjectId::from_hex(hex.trim().as_bytes()) {
+                out.push(oid);
+            }
+        }
+    }
+}
+
+/// Collects the tip commit ids of every remote-tracking branch
+/// (`refs/remotes/**`), both loose and packed.
+fn remote_tracking_tips(repo: &Repository) -> Vec<ObjectId> {
+    let git_dir = repo.git_dir();
+    let mut tips = Vec::new();
+    collect_loose_refs(&git_dir.join("refs").join("remotes"), &mut tips);
+
+    if let Ok(packed) = std::fs::read_to_string(git_dir.join("packed-refs")) {
+        for line in packed.lines() {
+            if line.starts_with('#') || line.starts_with('^') {
+                continue;
+            }
+            let Some((hex, name)) = line.split_once(' ') else {
+                continue;
+            };
+            if name.starts_with("refs/remotes/") {
+                if let Ok(oid) = ObjectId::from_hex(hex.as_bytes()) {
+                    tips.push(oid);
+                }
+            }
+        }
+    }
+
+    tips
+}
+
+/// Finds the nearest ancestor of `head` (inclusive) reachable from some
+/// remote-tracking branch, breadth-first. Returns `None` if no
+/// remote-tracking branch reaches `head`'s history at all.
+fn nearest_public_ancestor(repo: &Repository, head: ObjectId) -> Option<ObjectId> {
+    let public: HashSet<ObjectId> = remote_tracking_tips(repo)
+        .into_iter()
+        .flat_map(|tip| ancestors(repo, tip))
+        .collect();
+    if public.is_empty() {
+        return None;
+    }
+
+    let mut visited = HashSet::new();
+    let mut queue = std::collections::VecDeque::new();
+    queue.push_back(head);
+    while let Some(id) = queue.pop_front() {
+        if !visited.insert(id) {
+            continue;
+        }
+        if public.contains(&id) {
+            return Some(id);
+        }
+        if let Ok(data) = repo.find_object(id).map(|object| object.detach().data) {
+            queue.extend(commit_parent_ids(&data));
+        }
+    }
+    None
+}
+
+/// First line of `commit_data`'s commit message, the summary line by
+/// convention.
+fn commit_summary(commit_data: &[u8]) -> Option<String> {
+    let commit_text = std::str::from_utf8(commit_data).ok()?;
+    let (_headers, message) = commit_text.split_once("\n\n")?;
+    let summary = message.lines().next()?.trim();
+    (!summary.is_empty()).then(|| summary.to_string())
+}
+
+/// Finds a `Key: value` trailer (e.g. a Gerrit `Change-Id`) in `text`, used
+/// by [`Git::head_extra`] to pick out one named piece of metadata from a
+/// git note that may carry several.
+fn find_trailer(text: &[u8], key: &str) -> Option<String> {
+    let text = std::str::from_utf8(text).ok()?;
+    let prefix = format!("{key}: ");
+    text.lines()
+        .find_map(|line| line.strip_prefix(&prefix))
+        .map(|value| value.trim().to_string())
+}
+
+/// Reads the `refs/notes/commits` note attached to `commit_id`, if any.
+/// Notes are stored as a tree keyed by the annotated commit's full hex id,
+/// fanned out into `xx/` subdirectories (2 hex chars per level) once a
+/// flat tree would hold too many entries - [`find_note_blob`] walks either
+/// layout. Reads the note ref straight off disk the same way
+/// [`find_renamed_source`] and the other history-walking helpers in this
+/// file do, rather than going through a higher-level notes API.
+fn read_git_note(repo: &Repository, commit_id: ObjectId) -> Option<String> {
+    let notes_ref = read_ref(repo, "refs/notes/commits")?;
+    let notes_commit_data = repo.find_object(notes_ref).ok()?.detach().data;
+    let tree_id = commit_tree_id(&notes_commit_data)?;
+    let blob_id = find_note_blob(repo, tree_id, &commit_id.to_hex().to_string())?;
+    String::from_utf8(repo.find_object(blob_id).ok()?.detach().data).ok()
+}
+
+/// Reads a single ref (loose, under `.git/<ref_name>`, or packed, in
+/// `packed-refs`) to the object id it points at.
+fn read_ref(repo: &Repository, ref_name: &str) -> Option<ObjectId> {
+    let git_dir = repo.git_dir();
+    if let Ok(hex) = std::fs::read_to_string(git_dir.join(ref_name)) {
+        if let Ok(oid) = ObjectId::from_hex(hex.trim().as_bytes()) {
+            return Some(oid);
+        }
+    }
+
+    let packed = std::fs::read_to_string(git_dir.join("packed-refs")).ok()?;
+    packed.lines().find_map(|line| {
+        if line.starts_with('#') || line.starts_with('^') {
+            return None;
+        }
+        let (hex, name) = line.split_once(' ')?;
+        if name != ref_name {
+            return None;
+        }
+        ObjectId::from_hex(hex.as_bytes()).ok()
+    })
+}
+
+/// Walks the notes tree rooted at `tree_id` looking for the blob recorded
+/// under `hex_remaining` (initially the target commit's full hex id),
+/// matching either a flat entry (the whole remaining hex as one filename)
+/// or one more level of `xx/` fanout (first two hex chars as a subtree,
+/// recursing with the rest).
+fn find_note_blob(repo: &Repository, tree_id: ObjectId, hex_remaining: &str) -> Option<ObjectId> {
+    let data = repo.find_object(tree_id).ok()?.detach().data;
+    let mut i = 0;
+    while i < data.len() {
+        let space = i + data[i..].iter().position(|&b| b == b' ')?;
+        let mode = std::str::from_utf8(&data[i..space]).ok()?;
+        let nul = space + data[space..].iter().position(|&b| b == 0)?;
+        let name = std::str::from_utf8(&data[space + 1..nul]).ok()?;
+        let oid = ObjectId::try_from(&data[nul + 1..nul + 21]).ok()?;
+        i = nul + 21;
+
+        if name.eq_ignore_ascii_case(hex_remaining) {
+            return Some(oid);
+        }
+        if mode.starts_with('4')
+            && hex_remaining.len() > name.len()
+            && hex_remaining[..name.len()].eq_ignore_ascii_case(name)
+        {
+            if let Some(found) = find_note_blob(repo, oid, &hex_remaining[name.len()..]) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+/// Bounds [`blame_attribution`]'s history walk, the same way [`ancestors`]'s
+/// own `MAX_VISITED` bounds its.
+const MAX_BLAME_COMMITS: usize = 10_000;
+
+/// Bounds the file size [`diff_line_origins`]'s O(n*m) table will handle -
+/// past this the table itself would use an unreasonable amount of memory,
+/// so [`blame_attribution`] bails instead of grinding through it.
+const MAX_BLAME_LINES: usize = 20_000;
+
+/// Walks first-parent history from `HEAD`, diffing the file's content one
+/// commit at a time (see [`diff_line_origins`]) to find which commit last
+/// introduced each line still present at `HEAD` - the shared annotate pass
+/// behind both [`Git::blame_range`] and [`Git::blame_lines`], so a caller
+/// that needs several lines (contiguous or not) only pays for one walk.
+/// Returns the repository the attribution was computed against (so callers
+/// don't have to reopen it) alongside one commit per line of the file as it
+/// exists at `HEAD`.
+fn blame_attribution(file: &Path) -> Result<(Repository, Vec<ObjectId>)> {
+    let repo_dir = file.parent().context("file has no parent directory")?;
+    let repo = Git::open_repo(repo_dir, None)
+        .context("failed to open git repo")?
+        .to_thread_local();
+    let work_dir = repo.work_dir().context("repo has no worktree")?;
+    let rel_path = file.strip_prefix(work_dir)?;
+
+    let head = repo
+        .head_commit()
+        .context("repository has no commits yet")?;
+    let head_blob = file_blob_at_commit(&repo, head.id, rel_path)?.context("file is untracked")?;
+    let head_lines = split_lines(&head_blob);
+    if head_lines.len() > MAX_BLAME_LINES {
+        bail!(
+            "file has too many lines to blame ({} > {MAX_BLAME_LINES})",
+            head_lines.len()
+        );
+    }
+
+    let mut attribution: Vec<Option<ObjectId>> = vec![None; head_lines.len()];
+    let mut unresolved = head_lines.len();
+    let mut current_blob = head_blob;
+    let mut current_id = head.id;
+    // `result_index_of[k]` is the slot in `attribution` that the k-th
+    // line of `current_blob` will fill in once its origin is found, or
+    // `None` for a line that isn't being tracked back any further
+    // (already resolved, or not part of the requested history at all).
+    // Stays the same length as `current_blob`'s own line count, so it
+    // can always be zipped against `split_lines(&current_blob)`.
+    let mut result_index_of: Vec<Option<usize>> = (0..head_lines.len()).map(Some).collect();
+
+    for _ in 0..MAX_BLAME_COMMITS {
+        if unresolved == 0 {
+            break;
+        }
+
+        let commit_data = repo.find_object(current_id)?.detach().data;
+        let parent_id = commit_parent_ids(&commit_data).into_iter().next();
+        let parent_blob = match parent_id {
+            Some(id) => file_blob_at_commit(&repo, id, rel_path)?,
+            None => None,
+        };
+        let Some(parent_blob) = parent_blob else {
+            // Root commit, or the file didn't exist yet in the parent:
+            // every line still unresolved was introduced here.
+            for result_index in result_index_of.iter().flatten() {
+                if attribution[*result_index].is_none() {
+                    attribution[*result_index] = Some(current_id);
+                    unresolved -= 1;
+                }
+            }
+            break;
+        };
+
+        let current_lines = split_lines(&current_blob);
+        let parent_lines = split_lines(&parent_blob);
+        if parent_lines.len() > MAX_BLAME_LINES {
+            break;
+        }
+        let origins = diff_line_origins(&parent_lines, &current_lines);
+
+        let mut next_result_index_of = vec![None; parent_lines.len()];
+        for (k, origin) in origins.into_iter().enumerate() {
+            let Some(result_index) = result_index_of[k] else {
+                continue;
+            };
+            match origin {
+                Some(parent_index) => next_result_index_of[parent_index] = Some(result_index),
+                None => {
+                    attribution[result_index] = Some(current_id);
+                    unresolved -= 1;
+                }
+            }
+        }
+
+        result_index_of = next_result_index_of;
+        current_blob = parent_blob;
+        current_id = parent_id.unwrap();
+    }
+
+    for slot in &mut attribution {
+        if slot.is_none() {
+            // Ran out of history (hit a bound) before every line resolved;
+            // attribute the remainder to the oldest commit actually reached
+            // rather than silently dropping them.
+            *slot = Some(current_id);
+        }
+    }
+
+    Ok((
+        repo,
+        attribution
+            .into_iter()
+            .map(|slot| slot.expect("every line was attributed above"))
+            .collect(),
+    ))
+}
+
+/// Builds a single [`BlameInfo`] for `line`, attributed to `commit_id`,
+/// reusing `summaries` to avoid re-reading the same commit's message for
+/// every line it covers.
+fn blame_info(
+    repo: &Repository,
+    line: usize,
+    commit_id: ObjectId,
+    summaries: &mut HashMap<ObjectId, String>,
+) -> BlameInfo {
+    let summary = summaries.entry(commit_id).or_insert_with(|| {
+        repo.find_object(commit_id)
+            .ok()
+            .and_then(|object| commit_summary(&object.detach().data))
+            .unwrap_or_default()
+    });
+    BlameInfo {
+        line,
+        commit: commit_id.to_string(),
+        summary: summary.clone(),
+    }
+}
+
+/// Reads `rel_path`'s blob contents as recorded in `commit_id`'s tree, or
+/// `None` if the path isn't tracked there - same raw tree-walk as
+/// [`find_renamed_source`], just keyed by path instead of by content
+/// similarity.
+fn file_blob_at_commit(
+    repo: &Repository,
+    commit_id: ObjectId,
+    rel_path: &Path,
+) -> Result<Option<Vec<u8>>> {
+    let commit_data = repo.find_object(commit_id)?.detach().data;
+    let Some(tree_id) = commit_tree_id(&commit_data) else {
+        return Ok(None);
+    };
+    let mut tracked = HashMap::new();
+    walk_tree(repo, tree_id, &mut PathBuf::new(), &mut tracked)?;
+    match tracked.get(rel_path) {
+        Some(&oid) => Ok(Some(repo.find_object(oid)?.detach().data)),
+        None => Ok(None),
+    }
+}
+
+/// Splits `data` into lines without the trailing `\n`, the same convention
+/// `str::lines` uses - a lone trailing newline doesn't produce a phantom
+/// empty final line.
+fn split_lines(data: &[u8]) -> Vec<&[u8]> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let mut lines: Vec<&[u8]> = data.split(|&b| b == b'\n').collect();
+    if lines.last() == Some(&&b""[..]) {
+        lines.pop();
+    }
+    lines
+}
+
+/// For each line of `new`, finds the line of `old` it corresponds to (an
+/// unchanged line carried over), or `None` if it was added in `new` -
+/// via a classic LCS line diff. This only needs to know which lines are
+/// genuinely new for [`blame_attribution`], not to render a readable diff,
+/// so unlike `crate::diff`'s `imara-diff`-backed hunks this doesn't try to
+/// match a patience/histogram diff's notion of the "best" alignment, just
+/// *a* correct one.
+fn diff_line_origins(old: &[&[u8]], new: &[&[u8]]) -> Vec<Option<usize>> {
+    let (m, n) = (old.len(), new.len());
+    let mut dp = vec![vec![0u32; n + 1]; m + 1];
+    for i in (0..m).rev() {
+        for j in (0..n).rev() {
+            dp[i][j] = if old[i] == new[j] {
+                dp[i + 1][j + 1] + 1
+            } else {
+                dp[i + 1][j].max(dp[i][j + 1])
+            };
+        }
+    }
+
+    let mut origins = vec![None; n];
+    let (mut i, mut j) = (0, 0);
+    while i < m && j < n {
+        if old[i] == new[j] {
+            origins[j] = Some(i);
+            i += 1;
+            j += 1;
+        } else if dp[i + 1][j] >= dp[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    origins
+}
+
+/// Fraction of shared lines above which two blobs are considered the same
+/// file having been renamed, rather than an unrelated file.
+const RENAME_SIMILARITY_THRESHOLD: f32 = 0.5;
+
+/// When `file` isn't tracked at its current path (e.g. it was just renamed
+/// in the working copy, with no index update yet), looks for a tracked path
+/// that's gone missing from disk and whose content is similar enough to
+/// `file`'s current content to be its likely rename source. This keeps
+/// gutter diffs correct immediately after a rename, without relying on any
+/// explicit copy/rename metadata (git doesn't record one - renames are
+/// always inferred from content similarity, same as here).
+fn find_renamed_source(repo: &Repository, commit: &Commit, file: &Path) -> Option<ObjectId> {
+    let work_dir = repo.work_dir()?;
+    let current_contents = std::fs::read(file).ok()?;
+
+    let commit_data = repo.find_object(commit.id).ok()?.detach().data;
+    let tree_id = commit_tree_id(&commit_data)?;
+    let mut tracked = HashMap::new();
+    walk_tree(repo, tree_id, &mut PathBuf::new(), &mut tracked).ok()?;
+
+    tracked
+        .into_iter()
+        .filter(|(rel_path, _)| !work_dir.join(rel_path).exists())
+        .filter_map(|(_, oid)| {
+            let candidate_data = repo.find_object(oid).ok()?.detach().data;
+            let score = line_similarity(&candidate_data, &current_contents);
+            (score >= RENAME_SIMILARITY_THRESHOLD).then_some((score, oid))
+        })
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, oid)| oid)
+}
+
+/// Fraction of `a`'s lines that also appear in `b`, as a cheap, dependency-free
+/// proxy for "these are probably the same file". Not a real diff algorithm,
+/// but good enough to distinguish "renamed" from "unrelated file".
+fn line_similarity(a: &[u8], b: &[u8]) -> f32 {
+    let a_lines: HashSet<_> = a.split(|&b| b == b'\n').collect();
+    let b_lines: HashSet<_> = b.split(|&b| b == b'\n').collect();
+    if a_lines.is_empty() {
+        return 0.0;
     }
+    let shared = a_lines.intersection(&b_lines).count();
+    shared as f32 / a_lines.len() as f32
 }
 
-/// Finds the object that contains the contents of a file at a specific commit.
-fn find_file_in_commit(repo: &Repository, commit: &Commit, file: &Path) -> Result<ObjectId> {
+/// Finds the object that contains the contents of a file at a specific
+/// commit. Returns `Ok(None)` specifically when `file` is untracked at
+/// `commit` - distinguished from a real lookup failure (bad worktree, entry
+/// isn't a file, ...) so callers can tell "this file is simply new" apart
+/// from an actual error.
+fn find_file_in_commit(repo: &Repository, commit: &Commit, file: &Path) -> Result<Option<ObjectId>> {
     let repo_dir = repo.work_dir().context("repo has no worktree")?;
     let rel_path = file.strip_prefix(repo_dir)?;
     let tree = commit.tree()?;
-    let tree_entry = tree
-        .lookup_entry_by_path(rel_path, &mut Vec::new())?
-        .context("file is untracked")?;
+    let Some(tree_entry) = tree.lookup_entry_by_path(rel_path, &mut Vec::new())? else {
+        return Ok(None);
+    };
     match tree_entry.mode() {
         // not a file, everything is new, do not show diff
         mode @ (EntryMode::Tree | EntryMode::Commit | EntryMode::Link) => {
             bail!("entry at {} is not a file but a {mode:?}", file.display())
         }
         // found a file
-        EntryMode::Blob | EntryMode::BlobExecutable => Ok(tree_entry.object_id()),
+        EntryMode::Blob | EntryMode::BlobExecutable => Ok(Some(tree_entry.object_id())),
     }
 }
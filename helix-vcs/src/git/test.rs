@@ -1,8 +1,19 @@
-use std::{fs::File, io::Write, path::Path, process::Command};
+use std::{
+    collections::HashSet,
+    fs::File,
+    io::{Read, Write},
+    path::Path,
+    process::Command,
+};
+
+use crate::{ChangeKind, DiffProviderRegistry};
 
 use tempfile::TempDir;
 
-use crate::{DiffProvider, Git};
+use crate::{
+    invalidate_repo_cache, CancelToken, Cancelled, DiffProvider, Git, StatusConfig, TooLarge,
+    DEFAULT_MAX_DIFF_BASE_SIZE,
+};
 
 fn exec_git_cmd(args: &str, git_dir: &Path) {
     let res = Command::new("git")
@@ -119,3 +130,920 @@ fn symlink() {
     assert!(Git.get_diff_base(&file_link).is_err());
     assert_eq!(Git.get_diff_base(&file).unwrap(), Vec::from(contents));
 }
+
+#[test]
+fn get_diff_base_follows_a_working_directory_rename() {
+    let temp_git = empty_git_repo();
+    let old_path = temp_git.path().join("old_name.txt");
+    let contents = b"line one\nline two\nline three\n".as_slice();
+    File::create(&old_path).unwrap().write_all(contents).unwrap();
+    create_commit(temp_git.path(), true);
+
+    let new_path = temp_git.path().join("new_name.txt");
+    std::fs::rename(&old_path, &new_path).unwrap();
+
+    assert_eq!(Git.get_diff_base(&new_path).unwrap(), Vec::from(contents));
+}
+
+#[test]
+fn get_diff_base_is_empty_for_a_brand_new_file() {
+    let temp_git = empty_git_repo();
+    let committed = temp_git.path().join("committed.txt");
+    File::create(&committed).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    let new_file = temp_git.path().join("new_file.txt");
+    File::create(&new_file)
+        .unwrap()
+        .write_all(b"brand new content\n")
+        .unwrap();
+
+    // Untracked at HEAD, and not a rename of anything committed: the whole
+    // file is new, so the gutter should mark every line added rather than
+    // this erroring out.
+    assert_eq!(Git.get_diff_base(&new_file).unwrap(), Vec::new());
+}
+
+#[test]
+fn diff_base_reader_matches_eager_diff_base() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    let contents = b"foo".as_slice();
+    File::create(&file).unwrap().write_all(contents).unwrap();
+    create_commit(temp_git.path(), true);
+
+    let mut buf = Vec::new();
+    Git.get_diff_base_reader(&file)
+        .unwrap()
+        .read_to_end(&mut buf)
+        .unwrap();
+    assert_eq!(buf, Vec::from(contents));
+}
+
+#[test]
+fn head_extra_reads_a_trailer_from_the_head_commits_git_note() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    let res = Command::new("git")
+        .arg("-C")
+        .arg(temp_git.path())
+        .args(["notes", "add", "-m", "Change-Id: I1234567890"])
+        .env_remove("GIT_DIR")
+        .env("GIT_AUTHOR_DATE", "2000-01-01 00:00:00 +0000")
+        .env("GIT_AUTHOR_EMAIL", "author@example.com")
+        .env("GIT_AUTHOR_NAME", "author")
+        .env("GIT_COMMITTER_DATE", "2000-01-02 00:00:00 +0000")
+        .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+        .env("GIT_COMMITTER_NAME", "committer")
+        .env("GIT_CONFIG_COUNT", "1")
+        .env("GIT_CONFIG_KEY_0", "commit.gpgsign")
+        .env("GIT_CONFIG_VALUE_0", "false")
+        .output()
+        .expect("`git notes add` failed");
+    assert!(res.status.success());
+
+    assert_eq!(
+        Git.head_extra(&file, "Change-Id").unwrap(),
+        Some("I1234567890".to_string())
+    );
+    assert_eq!(Git.head_extra(&file, "Missing-Key").unwrap(), None);
+}
+
+#[test]
+fn head_extra_is_none_without_a_git_note() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert_eq!(Git.head_extra(&file, "Change-Id").unwrap(), None);
+}
+
+#[test]
+fn for_each_changed_file_reports_both_relative_and_absolute_paths() {
+    let temp_git = empty_git_repo();
+    let added = temp_git.path().join("added.txt");
+    File::create(&added).unwrap().write_all(b"new").unwrap();
+
+    let mut changes = Vec::new();
+    Git.for_each_changed_file(temp_git.path(), &StatusConfig::default(), &mut |change| {
+        changes.push(change.unwrap());
+    })
+    .unwrap();
+
+    let change = changes
+        .iter()
+        .find(|change| change.path == Path::new("added.txt"))
+        .unwrap();
+    assert_eq!(change.abs_path, added);
+}
+
+#[test]
+fn for_each_changed_file_reports_added_modified_and_deleted() {
+    let temp_git = empty_git_repo();
+    let tracked = temp_git.path().join("tracked.txt");
+    let removed = temp_git.path().join("removed.txt");
+    File::create(&tracked).unwrap().write_all(b"foo").unwrap();
+    File::create(&removed).unwrap().write_all(b"bar").unwrap();
+    create_commit(temp_git.path(), true);
+
+    File::create(&tracked).unwrap().write_all(b"baz").unwrap();
+    std::fs::remove_file(&removed).unwrap();
+    let added = temp_git.path().join("added.txt");
+    File::create(&added).unwrap().write_all(b"new").unwrap();
+
+    let mut changes = Vec::new();
+    Git.for_each_changed_file(temp_git.path(), &StatusConfig::default(), &mut |change| {
+        changes.push(change.unwrap());
+    })
+    .unwrap();
+
+    let kinds: HashSet<_> = changes
+        .iter()
+        .map(|change| (change.path.clone(), change.kind))
+        .collect();
+    assert!(kinds.contains(&("tracked.txt".into(), ChangeKind::Modified)));
+    assert!(kinds.contains(&("removed.txt".into(), ChangeKind::Deleted)));
+    assert!(kinds.contains(&("added.txt".into(), ChangeKind::Added)));
+}
+
+#[test]
+fn changed_files_sorted_orders_limits_and_dedupes() {
+    let temp_git = empty_git_repo();
+    File::create(temp_git.path().join("b.txt"))
+        .unwrap()
+        .write_all(b"foo")
+        .unwrap();
+    File::create(temp_git.path().join("a.txt"))
+        .unwrap()
+        .write_all(b"foo")
+        .unwrap();
+    create_commit(temp_git.path(), true);
+    File::create(temp_git.path().join("b.txt"))
+        .unwrap()
+        .write_all(b"bar")
+        .unwrap();
+    File::create(temp_git.path().join("a.txt"))
+        .unwrap()
+        .write_all(b"bar")
+        .unwrap();
+
+    let registry = DiffProviderRegistry::default();
+    let by_path = registry
+        .changed_files_sorted(temp_git.path(), crate::ChangeSortKey::Path, None)
+        .unwrap();
+    assert_eq!(
+        by_path.iter().map(|c| c.path.clone()).collect::<Vec<_>>(),
+        vec![Path::new("a.txt").to_path_buf(), Path::new("b.txt").to_path_buf()]
+    );
+
+    let limited = registry
+        .changed_files_sorted(temp_git.path(), crate::ChangeSortKey::Path, Some(1))
+        .unwrap();
+    assert_eq!(limited.len(), 1);
+    assert_eq!(limited[0].path, Path::new("a.txt"));
+}
+
+#[test]
+fn changed_file_counts_tallies_without_materializing_paths() {
+    let temp_git = empty_git_repo();
+    let tracked = temp_git.path().join("tracked.txt");
+    File::create(&tracked).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+    File::create(&tracked).unwrap().write_all(b"bar").unwrap();
+    File::create(temp_git.path().join("added.txt"))
+        .unwrap()
+        .write_all(b"new")
+        .unwrap();
+
+    let registry = DiffProviderRegistry::default();
+    let counts = registry.changed_file_counts(temp_git.path()).unwrap();
+    assert_eq!(counts.modified, 1);
+    assert_eq!(counts.added, 1);
+    assert_eq!(counts.deleted, 0);
+}
+
+#[test]
+fn for_each_changed_file_classifies_conflict_markers_during_a_merge() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    File::create(&file)
+        .unwrap()
+        .write_all(b"<<<<<<< HEAD\nfoo\n=======\nbar\n>>>>>>> branch\n")
+        .unwrap();
+    File::create(temp_git.path().join(".git").join("MERGE_HEAD"))
+        .unwrap()
+        .write_all(b"0000000000000000000000000000000000000000\n")
+        .unwrap();
+
+    let mut changes = Vec::new();
+    Git.for_each_changed_file(temp_git.path(), &StatusConfig::default(), &mut |change| {
+        changes.push(change.unwrap());
+    })
+    .unwrap();
+
+    assert!(changes
+        .iter()
+        .any(|change| change.path == Path::new("file.txt") && change.kind == ChangeKind::Conflict));
+}
+
+/// Git's equivalent of a shared/linked checkout is a worktree: the working
+/// copy's `.git` is a *file* (not a directory) pointing at the real store via
+/// a `gitdir:` line, analogous to hg's `.hg/sharedpath`. `Git::open_repo`
+/// already resolves this correctly because it goes through
+/// `gix::discover`, which follows that indirection natively - there's no
+/// separate "shared store" concept to add on the git side.
+#[test]
+fn diff_base_resolves_through_a_linked_worktree() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    let contents = b"foo".as_slice();
+    File::create(&file).unwrap().write_all(contents).unwrap();
+    create_commit(temp_git.path(), true);
+    exec_git_cmd("branch other", temp_git.path());
+
+    let worktree_dir = temp_git.path().parent().unwrap().join("worktree-checkout");
+    exec_git_cmd(
+        &format!("worktree add {} other", worktree_dir.display()),
+        temp_git.path(),
+    );
+
+    let worktree_file = worktree_dir.join("file.txt");
+    assert_eq!(Git.get_diff_base(&worktree_file).unwrap(), Vec::from(contents));
+
+    std::fs::remove_dir_all(&worktree_dir).ok();
+}
+
+#[test]
+fn head_name_shows_both_parents_during_a_merge() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    let plain_name = Git.get_current_head_name(&file).unwrap().load().to_string();
+    assert!(!plain_name.contains("MERGE"));
+
+    File::create(temp_git.path().join(".git").join("MERGE_HEAD"))
+        .unwrap()
+        .write_all(b"0000000000000000000000000000000000000000\n")
+        .unwrap();
+
+    let merge_name = Git.get_current_head_name(&file).unwrap().load().to_string();
+    assert!(merge_name.contains("MERGE"));
+    assert!(merge_name.contains("0000000"));
+}
+
+#[test]
+fn for_each_changed_file_classifies_add_add_conflicts_distinctly() {
+    let temp_git = empty_git_repo();
+    create_commit(temp_git.path(), true);
+
+    let file = temp_git.path().join("new.txt");
+    File::create(&file)
+        .unwrap()
+        .write_all(b"<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> branch\n")
+        .unwrap();
+    File::create(temp_git.path().join(".git").join("MERGE_HEAD"))
+        .unwrap()
+        .write_all(b"0000000000000000000000000000000000000000\n")
+        .unwrap();
+
+    let mut changes = Vec::new();
+    Git.for_each_changed_file(temp_git.path(), &StatusConfig::default(), &mut |change| {
+        changes.push(change.unwrap());
+    })
+    .unwrap();
+
+    assert!(changes
+        .iter()
+        .any(|change| change.path == Path::new("new.txt") && change.kind == ChangeKind::Conflict));
+    assert!(!changes
+        .iter()
+        .any(|change| change.path == Path::new("new.txt") && change.kind == ChangeKind::Added));
+}
+
+#[test]
+fn has_conflicts_is_false_outside_a_merge_and_true_once_markers_appear() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    File::create(&file).unwrap().write_all(b"bar").unwrap();
+    assert!(!Git.has_conflicts(temp_git.path()).unwrap());
+
+    File::create(&file)
+        .unwrap()
+        .write_all(b"<<<<<<< HEAD\nfoo\n=======\nbar\n>>>>>>> branch\n")
+        .unwrap();
+    // Conflict markers alone aren't enough without a merge actually in
+    // progress.
+    assert!(!Git.has_conflicts(temp_git.path()).unwrap());
+
+    File::create(temp_git.path().join(".git").join("MERGE_HEAD"))
+        .unwrap()
+        .write_all(b"0000000000000000000000000000000000000000\n")
+        .unwrap();
+    assert!(Git.has_conflicts(temp_git.path()).unwrap());
+}
+
+#[test]
+fn current_commit_summary_reads_and_truncates_the_first_message_line() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+
+    let res = Command::new("git")
+        .arg("-C")
+        .arg(temp_git.path())
+        .args([
+            "commit",
+            "-m",
+            "Add the first file\n\nSome body text that isn't part of the summary",
+        ])
+        .env_remove("GIT_DIR")
+        .env("GIT_AUTHOR_DATE", "2000-01-01 00:00:00 +0000")
+        .env("GIT_AUTHOR_EMAIL", "author@example.com")
+        .env("GIT_AUTHOR_NAME", "author")
+        .env("GIT_COMMITTER_DATE", "2000-01-02 00:00:00 +0000")
+        .env("GIT_COMMITTER_EMAIL", "committer@example.com")
+        .env("GIT_COMMITTER_NAME", "committer")
+        .env("GIT_CONFIG_COUNT", "1")
+        .env("GIT_CONFIG_KEY_0", "commit.gpgsign")
+        .env("GIT_CONFIG_VALUE_0", "false")
+        .output()
+        .expect("`git commit` failed");
+    assert!(res.status.success());
+
+    assert_eq!(
+        Git.current_commit_summary(&file, None).unwrap(),
+        "Add the first file"
+    );
+    assert_eq!(
+        Git.current_commit_summary(&file, Some(8)).unwrap(),
+        "Add the…"
+    );
+}
+
+#[test]
+fn current_commit_summary_errors_clearly_with_no_commits_yet() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+
+    assert!(Git.current_commit_summary(&file, None).is_err());
+}
+
+fn head_oid(repo: &Path) -> String {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .expect("`git rev-parse HEAD` failed");
+    assert!(output.status.success());
+    String::from_utf8_lossy(&output.stdout).trim().to_string()
+}
+
+#[test]
+fn blame_range_attributes_each_line_to_the_commit_that_last_changed_it() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+
+    File::create(&file).unwrap().write_all(b"a\nb\nc\n").unwrap();
+    create_commit(temp_git.path(), true);
+    let first = head_oid(temp_git.path());
+
+    File::create(&file).unwrap().write_all(b"a\nX\nc\n").unwrap();
+    create_commit(temp_git.path(), true);
+    let second = head_oid(temp_git.path());
+
+    File::create(&file)
+        .unwrap()
+        .write_all(b"a\nX\nc\nd\n")
+        .unwrap();
+    create_commit(temp_git.path(), true);
+    let third = head_oid(temp_git.path());
+
+    let blame = Git.blame_range(&file, 0..4).unwrap();
+    let commits: Vec<&str> = blame.iter().map(|info| info.commit.as_str()).collect();
+    assert_eq!(commits, vec![first.as_str(), second.as_str(), first.as_str(), third.as_str()]);
+    assert_eq!(blame.iter().map(|info| info.line).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+    // A sub-range only returns the lines actually asked for.
+    let partial = Git.blame_range(&file, 1..2).unwrap();
+    assert_eq!(partial.len(), 1);
+    assert_eq!(partial[0].commit, second);
+}
+
+#[test]
+fn blame_range_errors_clearly_past_the_end_of_the_file() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"a\nb\n").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert!(Git.blame_range(&file, 0..10).is_err());
+}
+
+#[test]
+fn blame_lines_matches_blame_range_for_a_scattered_set_of_lines() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+
+    File::create(&file).unwrap().write_all(b"a\nb\nc\n").unwrap();
+    create_commit(temp_git.path(), true);
+    let first = head_oid(temp_git.path());
+
+    File::create(&file).unwrap().write_all(b"a\nX\nc\n").unwrap();
+    create_commit(temp_git.path(), true);
+    let second = head_oid(temp_git.path());
+
+    let blame = Git.blame_lines(&file, &[0, 2]).unwrap();
+    assert_eq!(blame.len(), 2);
+    assert_eq!(blame[&0].commit, first);
+    assert_eq!(blame[&2].commit, first);
+    assert!(!blame.contains_key(&1));
+
+    let blame = Git.blame_lines(&file, &[1]).unwrap();
+    assert_eq!(blame[&1].commit, second);
+}
+
+#[test]
+fn blame_lines_errors_clearly_past_the_end_of_the_file() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"a\nb\n").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert!(Git.blame_lines(&file, &[0, 10]).is_err());
+}
+
+#[test]
+fn repo_capabilities_reports_a_working_directory() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    let caps = Git.repo_capabilities(&file).unwrap();
+    assert!(caps.has_work_dir);
+    assert!(caps.writable);
+    assert_eq!(caps.provider, "git");
+}
+
+#[test]
+fn repo_capabilities_reports_no_working_directory_for_a_bare_repo() {
+    let tmp = tempfile::tempdir().expect("create temp dir for git testing");
+    exec_git_cmd("init --bare", tmp.path());
+    // A bare repo has no worktree to place a file in, so point at a
+    // not-necessarily-existing path inside it purely to anchor the lookup.
+    let file = tmp.path().join("file.txt");
+
+    let caps = Git.repo_capabilities(&file).unwrap();
+    assert!(!caps.has_work_dir);
+    assert!(!caps.writable);
+    assert_eq!(caps.provider, "git");
+}
+
+#[test]
+fn merge_base_and_diff_base_rev_follow_branch_history() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"base").unwrap();
+    create_commit(temp_git.path(), true);
+
+    exec_git_cmd("branch feature", temp_git.path());
+
+    File::create(&file).unwrap().write_all(b"main").unwrap();
+    create_commit(temp_git.path(), true);
+    let main_name = Git.get_current_head_name(&file).unwrap().load().to_string();
+
+    exec_git_cmd("checkout feature", temp_git.path());
+    File::create(&file).unwrap().write_all(b"feature").unwrap();
+    create_commit(temp_git.path(), true);
+
+    let base_rev = Git.merge_base(&file, "HEAD", &main_name).unwrap();
+    let base_contents = Git.get_diff_base_rev(&file, &base_rev).unwrap();
+    assert_eq!(base_contents, b"base");
+}
+
+#[test]
+fn get_file_on_ref_resolves_a_branch_name() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"on main").unwrap();
+    create_commit(temp_git.path(), true);
+    let main_name = Git.get_current_head_name(&file).unwrap().load().to_string();
+
+    exec_git_cmd("branch feature", temp_git.path());
+    exec_git_cmd("checkout feature", temp_git.path());
+    File::create(&file).unwrap().write_all(b"on feature").unwrap();
+    create_commit(temp_git.path(), true);
+
+    let contents = Git.get_file_on_ref(&file, &main_name).unwrap();
+    assert_eq!(contents, b"on main");
+}
+
+#[test]
+fn get_file_on_ref_errors_clearly_for_an_unknown_name() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"v1").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert!(Git.get_file_on_ref(&file, "no-such-branch").is_err());
+}
+
+#[test]
+fn diff_base_public_is_empty_without_any_remote() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"v1").unwrap();
+    create_commit(temp_git.path(), true);
+
+    // No remote configured at all: everything is still "draft".
+    assert_eq!(Git.get_diff_base_public(&file).unwrap(), Vec::<u8>::new());
+}
+
+#[test]
+fn diff_base_public_follows_nearest_remote_tracking_ancestor() {
+    let remote = tempfile::tempdir().expect("create temp dir for git testing");
+    exec_git_cmd("init --bare", remote.path());
+
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"pushed").unwrap();
+    create_commit(temp_git.path(), true);
+
+    exec_git_cmd(
+        &format!("push {} HEAD:refs/heads/main", remote.path().display()),
+        temp_git.path(),
+    );
+    // Write the remote-tracking ref the same way `git fetch` would, so
+    // `refs/remotes/origin/main` matches what a real fetch would have left
+    // behind rather than just pushed `refs/heads/main` on the remote.
+    exec_git_cmd(
+        &format!("fetch {} main:refs/remotes/origin/main", remote.path().display()),
+        temp_git.path(),
+    );
+
+    File::create(&file).unwrap().write_all(b"unpushed").unwrap();
+    create_commit(temp_git.path(), true);
+
+    let base = Git.get_diff_base_public(&file).unwrap();
+    assert_eq!(base, b"pushed");
+}
+
+#[test]
+fn for_each_changed_file_with_progress_reports_final_count() {
+    let temp_git = empty_git_repo();
+    File::create(temp_git.path().join("initial.txt"))
+        .unwrap()
+        .write_all(b"initial")
+        .unwrap();
+    create_commit(temp_git.path(), true);
+
+    for i in 0..3 {
+        File::create(temp_git.path().join(format!("file{i}.txt")))
+            .unwrap()
+            .write_all(b"contents")
+            .unwrap();
+    }
+
+    let mut changes = Vec::new();
+    let mut last_reported = 0;
+    Git.for_each_changed_file_with_progress(
+        temp_git.path(),
+        &StatusConfig::default(),
+        &mut |change| changes.push(change.unwrap()),
+        &mut |scanned| last_reported = scanned,
+    )
+    .unwrap();
+
+    assert_eq!(changes.len(), 3);
+    // Below the throttle threshold, the only guaranteed call is the final
+    // one with the total scanned count: the three new files plus the
+    // unchanged `initial.txt`.
+    assert_eq!(last_reported, 4);
+}
+
+#[test]
+fn head_state_is_unborn_before_the_first_commit() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+
+    match Git.head_state(&file).unwrap() {
+        crate::HeadState::Unborn(branch) => assert!(!branch.is_empty()),
+        other => panic!("expected Unborn, got {other:?}"),
+    }
+}
+
+#[test]
+fn head_state_is_named_after_the_first_commit() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    match Git.head_state(&file).unwrap() {
+        crate::HeadState::Named(branch) => assert!(!branch.is_empty()),
+        other => panic!("expected Named, got {other:?}"),
+    }
+}
+
+#[test]
+fn head_state_is_detached_at_a_specific_commit() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+    exec_git_cmd("checkout --detach HEAD", temp_git.path());
+
+    match Git.head_state(&file).unwrap() {
+        crate::HeadState::Detached(rev) => assert!(!rev.is_empty()),
+        other => panic!("expected Detached, got {other:?}"),
+    }
+}
+
+#[test]
+fn diff_base_source_index_reads_a_staged_but_uncommitted_file() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+
+    // Nothing committed yet: `Head` has nothing to show.
+    assert!(Git
+        .get_diff_base_source(&file, crate::DiffBaseSource::Head)
+        .is_err());
+    assert_eq!(
+        Git.get_diff_base_source(&file, crate::DiffBaseSource::Index)
+            .unwrap(),
+        Vec::from(b"foo".as_slice())
+    );
+    assert_eq!(
+        Git.get_diff_base_source(&file, crate::DiffBaseSource::Auto)
+            .unwrap(),
+        Vec::from(b"foo".as_slice())
+    );
+}
+
+#[test]
+fn file_mode_reports_regular_and_executable_files() {
+    let temp_git = empty_git_repo();
+    let regular = temp_git.path().join("regular.txt");
+    File::create(&regular).unwrap().write_all(b"foo").unwrap();
+    let executable = temp_git.path().join("executable.sh");
+    File::create(&executable).unwrap().write_all(b"#!/bin/sh").unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&executable, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    create_commit(temp_git.path(), true);
+
+    assert_eq!(Git.file_mode(&regular).unwrap(), crate::FileMode::Regular);
+    #[cfg(unix)]
+    assert_eq!(
+        Git.file_mode(&executable).unwrap(),
+        crate::FileMode::Executable
+    );
+}
+
+#[test]
+fn file_mode_errors_for_an_untracked_file() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+
+    assert!(Git.file_mode(&file).is_err());
+}
+
+#[test]
+fn changed_files_between_reports_added_modified_and_deleted() {
+    let temp_git = empty_git_repo();
+    let modified = temp_git.path().join("modified.txt");
+    let removed = temp_git.path().join("removed.txt");
+    File::create(&modified).unwrap().write_all(b"foo").unwrap();
+    File::create(&removed).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+    exec_git_cmd("tag rev-a", temp_git.path());
+
+    File::create(&modified).unwrap().write_all(b"bar").unwrap();
+    std::fs::remove_file(&removed).unwrap();
+    let added = temp_git.path().join("added.txt");
+    File::create(&added).unwrap().write_all(b"new").unwrap();
+    create_commit(temp_git.path(), true);
+    exec_git_cmd("tag rev-b", temp_git.path());
+
+    let mut changes = Git
+        .changed_files_between(&modified, "rev-a", "rev-b")
+        .unwrap();
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+
+    assert_eq!(changes.len(), 3);
+    assert_eq!(changes[0].path, Path::new("added.txt"));
+    assert_eq!(changes[0].kind, ChangeKind::Added);
+    assert_eq!(changes[1].path, Path::new("modified.txt"));
+    assert_eq!(changes[1].kind, ChangeKind::Modified);
+    assert_eq!(changes[2].path, Path::new("removed.txt"));
+    assert_eq!(changes[2].kind, ChangeKind::Deleted);
+}
+
+#[test]
+fn changed_files_between_rejects_an_unresolvable_revision() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert!(Git
+        .changed_files_between(&file, "HEAD", "does-not-exist")
+        .is_err());
+}
+
+#[test]
+fn diff_base_source_index_reflects_staged_changes_not_yet_committed() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    File::create(&file).unwrap().write_all(b"bar").unwrap();
+    exec_git_cmd("add -A", temp_git.path());
+    File::create(&file).unwrap().write_all(b"baz").unwrap();
+
+    assert_eq!(
+        Git.get_diff_base_source(&file, crate::DiffBaseSource::Head)
+            .unwrap(),
+        Vec::from(b"foo".as_slice())
+    );
+    assert_eq!(
+        Git.get_diff_base_source(&file, crate::DiffBaseSource::Index)
+            .unwrap(),
+        Vec::from(b"bar".as_slice())
+    );
+    assert_eq!(
+        Git.get_diff_base_source(&file, crate::DiffBaseSource::Auto)
+            .unwrap(),
+        Vec::from(b"bar".as_slice())
+    );
+}
+
+#[test]
+fn diff_base_source_index_errors_for_an_unstaged_file() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+
+    assert!(Git
+        .get_diff_base_source(&file, crate::DiffBaseSource::Index)
+        .is_err());
+}
+
+#[test]
+fn diff_base_source_auto_falls_back_to_head_when_unstaged() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+    File::create(&file).unwrap().write_all(b"bar").unwrap();
+
+    assert_eq!(
+        Git.get_diff_base_source(&file, crate::DiffBaseSource::Auto)
+            .unwrap(),
+        Vec::from(b"foo".as_slice())
+    );
+}
+
+#[test]
+fn same_tracked_source_detects_identical_tracked_content() {
+    let temp_git = empty_git_repo();
+    let a = temp_git.path().join("a.txt");
+    let b = temp_git.path().join("b.txt");
+    File::create(&a).unwrap().write_all(b"shared").unwrap();
+    File::create(&b).unwrap().write_all(b"shared").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert!(Git.same_tracked_source(&a, &b).unwrap());
+}
+
+#[test]
+fn same_tracked_source_rejects_diverged_or_untracked_files() {
+    let temp_git = empty_git_repo();
+    let a = temp_git.path().join("a.txt");
+    let b = temp_git.path().join("b.txt");
+    File::create(&a).unwrap().write_all(b"one").unwrap();
+    File::create(&b).unwrap().write_all(b"two").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert!(!Git.same_tracked_source(&a, &b).unwrap());
+
+    let untracked = temp_git.path().join("untracked.txt");
+    File::create(&untracked).unwrap().write_all(b"one").unwrap();
+    assert!(!Git.same_tracked_source(&a, &untracked).unwrap());
+}
+
+#[test]
+fn get_diff_base_rejects_blobs_over_the_size_limit() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("huge.bin");
+    let oversized = vec![0u8; DEFAULT_MAX_DIFF_BASE_SIZE as usize + 1];
+    File::create(&file).unwrap().write_all(&oversized).unwrap();
+    create_commit(temp_git.path(), true);
+
+    let err = Git.get_diff_base(&file).unwrap_err();
+    assert!(err.downcast_ref::<TooLarge>().is_some());
+}
+
+#[test]
+fn for_each_changed_file_cancellable_bails_when_already_cancelled() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("a.txt");
+    File::create(&file).unwrap().write_all(b"one").unwrap();
+
+    let cancel = CancelToken::none();
+    cancel.cancel();
+
+    let err = Git
+        .for_each_changed_file_cancellable(
+            temp_git.path(),
+            &StatusConfig::default(),
+            &mut |_| {},
+            &cancel,
+        )
+        .unwrap_err();
+    assert!(err.downcast_ref::<Cancelled>().is_some());
+}
+
+#[test]
+fn repo_snapshot_combines_head_name_counts_and_dirty_flag() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"foo").unwrap();
+    create_commit(temp_git.path(), true);
+
+    File::create(&file).unwrap().write_all(b"bar").unwrap();
+    let other = temp_git.path().join("other.txt");
+    File::create(&other).unwrap().write_all(b"new").unwrap();
+
+    let snapshot = Git.repo_snapshot(&file).unwrap();
+    assert_eq!(snapshot.counts.modified, 1);
+    assert_eq!(snapshot.counts.added, 1);
+    assert!(snapshot.file_dirty);
+    assert!(!snapshot.head_name.load().is_empty());
+}
+
+#[test]
+fn capabilities_reports_blame_history_and_staged_diff_support() {
+    let caps = Git.capabilities();
+    assert!(caps.blame);
+    assert!(caps.history);
+    assert!(caps.staged_diff);
+}
+
+#[test]
+fn invalidate_repo_cache_none_clears_everything_without_breaking_lookups() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"base").unwrap();
+    create_commit(temp_git.path(), true);
+
+    // Populate the cached repository handle.
+    assert_eq!(Git.get_diff_base(&file).unwrap(), b"base");
+
+    invalidate_repo_cache(None);
+
+    // A dropped cache just means the next lookup rediscovers the repository
+    // from disk; the result is unaffected.
+    assert_eq!(Git.get_diff_base(&file).unwrap(), b"base");
+}
+
+#[test]
+fn invalidate_repo_cache_with_a_root_only_drops_matching_entries() {
+    let temp_git = empty_git_repo();
+    let file = temp_git.path().join("file.txt");
+    File::create(&file).unwrap().write_all(b"base").unwrap();
+    create_commit(temp_git.path(), true);
+
+    assert_eq!(Git.get_diff_base(&file).unwrap(), b"base");
+
+    // An unrelated root leaves this repo's cached entry untouched.
+    invalidate_repo_cache(Some(Path::new("/does/not/exist")));
+    assert_eq!(Git.get_diff_base(&file).unwrap(), b"base");
+
+    // Invalidating the repo's own root drops it; the next lookup still
+    // succeeds by rediscovering it from disk.
+    invalidate_repo_cache(Some(temp_git.path()));
+    assert_eq!(Git.get_diff_base(&file).unwrap(), b"base");
+}
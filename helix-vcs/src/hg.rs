@@ -0,0 +1,474 @@
+//! Mercurial support, behind the `hg` feature.
+//!
+//! Everything that actually talks to `hg` lives behind the narrow
+//! [`adapter::Backend`] trait in [`adapter`] rather than being called
+//! directly from [`Hg`]'s [`DiffProvider`] methods. `hg`'s own Rust crates
+//! have shifted API shape across versions before (`dirstate_map_mut`,
+//! `with_status`, `revset`, ...); keeping one seam here means an upgrade
+//! only has to change [`adapter`], not every call site.
+
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use arc_swap::ArcSwap;
+use std::sync::Arc;
+
+use crate::{
+    BlameInfo, ChangeCounts, DiffProvider, FileChange, FileMode, ProviderCapabilities,
+    RepoSnapshot, StatusConfig, TooLarge,
+};
+
+mod adapter;
+use adapter::{Backend, CliBackend};
+
+pub struct Hg<B: Backend = CliBackend> {
+    backend: B,
+}
+
+impl Default for Hg<CliBackend> {
+    fn default() -> Self {
+        Hg {
+            backend: CliBackend,
+        }
+    }
+}
+
+impl<B: Backend> Hg<B> {
+    /// Files marked unresolved (`U`) in `file`'s repository mergestate, read
+    /// directly via the backend rather than inferred from
+    /// [`DiffProvider::for_each_changed_file`]'s status walk - authoritative
+    /// and doesn't require re-deriving conflict state from a full status
+    /// diff, for something like a "jump to next conflict" command.
+    /// Resolved-but-still-modified files don't appear here even though
+    /// they'd show up as modified in status. Empty when there's no merge in
+    /// progress.
+    pub fn unresolved_files(&self, file: &Path) -> Result<Vec<PathBuf>> {
+        let (repo_root, _) = adapter::locate(file)?;
+        self.backend.unresolved_files(&repo_root)
+    }
+
+    /// Whether discarding `file`'s uncommitted changes (reverting it back to
+    /// the working parent) is safe to do without a confirmation prompt.
+    /// Reuses [`Backend::phase`] (is the working parent `public`, i.e.
+    /// already shared and conventionally immutable) and
+    /// [`Backend::working_parents`] (is there an uncommitted merge, which a
+    /// plain revert would silently collapse back to a single parent) rather
+    /// than re-deriving either from a status walk. Purely informational -
+    /// this never blocks the discard itself, it only tells a caller whether
+    /// to ask first.
+    pub fn can_safely_discard(&self, file: &Path) -> Result<DiscardSafety> {
+        let (repo_root, _) = adapter::locate(file)?;
+        let phase = self.backend.phase(&repo_root, ".")?;
+        let parents = self.backend.working_parents(&repo_root)?;
+        Ok(DiscardSafety {
+            base_is_public: phase == "public",
+            has_uncommitted_merge: parents.len() > 1,
+        })
+    }
+
+    /// The nearest tag reachable from the working parent, formatted as
+    /// `tagname+<distance>` when the working parent isn't exactly tagged
+    /// (or plain `tagname` when it is) - hg's equivalent of `git describe`.
+    /// `None` if no tag is reachable at all. A separate opt-in method
+    /// rather than folded into [`DiffProvider::get_current_head_name`]:
+    /// computing the distance walks history, which isn't free on a large
+    /// repo, so it should only run when a caller actually wants it.
+    pub fn nearest_tag(&self, file: &Path) -> Result<Option<String>> {
+        let (repo_root, _) = adapter::locate(file)?;
+        Ok(self
+            .backend
+            .nearest_tag(&repo_root, ".")?
+            .map(|(tag, distance)| {
+                if distance == 0 {
+                    tag
+                } else {
+                    format!("{tag}+{distance}")
+                }
+            }))
+    }
+}
+
+/// The result of [`Hg::can_safely_discard`]'s safety check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiscardSafety {
+    /// The working parent's phase is `public`: it's been pushed/shared and
+    /// is conventionally treated as immutable, so discarding changes on top
+    /// of it (e.g. before an amend) is more surprising than usual.
+    pub base_is_public: bool,
+    /// The working copy has more than one parent (a merge not yet
+    /// committed); discarding changes here collapses that merge instead of
+    /// just reverting edits.
+    pub has_uncommitted_merge: bool,
+}
+
+impl DiscardSafety {
+    /// Whether this is an unremarkable discard that doesn't need a
+    /// confirmation prompt.
+    pub fn is_safe(&self) -> bool {
+        !self.base_is_public && !self.has_uncommitted_merge
+    }
+}
+
+/// Returned (downcast via [`anyhow::Error::downcast_ref`]) when a
+/// `largefiles` standin resolves to a hash that isn't present in the
+/// repository's local largefiles cache, so a caller can show "largefile not
+/// fetched" instead of a generic read failure.
+#[derive(Debug, Clone)]
+pub struct LargefileNotAvailable {
+    pub hash: String,
+}
+
+impl std::fmt::Display for LargefileNotAvailable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "largefile {} is not present in the local cache", self.hash)
+    }
+}
+
+impl std::error::Error for LargefileNotAvailable {}
+
+/// The `largefiles` extension's standin path for `rel_path`: the tracked
+/// file `.hglf/<rel_path>` holding the real content's hash, in place of
+/// `rel_path` itself.
+fn largefile_standin_path(rel_path: &Path) -> PathBuf {
+    Path::new(".hglf").join(rel_path)
+}
+
+/// Reads a largefile's real content out of the repository's local cache
+/// (`.hg/largefiles/<hash>`) given the hash recorded in its standin.
+/// [`LargefileNotAvailable`] if the object was never fetched into this
+/// clone (e.g. `hg lfpull` hasn't been run).
+fn resolve_largefile(repo_root: &Path, hash: &str) -> Result<Vec<u8>> {
+    let cache_path = repo_root.join(".hg").join("largefiles").join(hash);
+    std::fs::read(&cache_path).map_err(|_| {
+        LargefileNotAvailable {
+            hash: hash.to_string(),
+        }
+        .into()
+    })
+}
+
+impl<B: Backend> DiffProvider for Hg<B> {
+    fn get_diff_base(&self, file: &Path) -> Result<Vec<u8>> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+
+        // The `largefiles` extension tracks a standin at `.hglf/<path>`
+        // (holding the real file's hash) instead of `<path>` itself, so a
+        // largefile's standin is checked first: finding one means `cat`ing
+        // `<path>` directly would fail (or worse, silently return nothing),
+        // since core `hg` never tracked that path at all.
+        let standin = largefile_standin_path(&rel_path);
+        if self.backend.is_tracked_at(&repo_root, ".", &standin) {
+            let hash = self.backend.cat(&repo_root, ".", &standin)?;
+            let hash = String::from_utf8(hash)
+                .context("largefile standin does not contain a valid hash")?;
+            let data = resolve_largefile(&repo_root, hash.trim())?;
+            if let Some(limit) = self.max_diff_base_size() {
+                let size = data.len() as u64;
+                if size > limit {
+                    return Err(TooLarge { size, limit }.into());
+                }
+            }
+            return Ok(data);
+        }
+
+        if !self.backend.is_tracked_at(&repo_root, ".", &rel_path) {
+            // Added but not yet committed (or never tracked at all): the
+            // whole file is new, so diff against nothing rather than
+            // surfacing `hg cat`'s "no such file in rev" as an error.
+            return Ok(Vec::new());
+        }
+        let data = self.backend.cat(&repo_root, ".", &rel_path)?;
+
+        // `hg cat` already has to read the whole file to print it, so
+        // there's no cheaper manifest-only size to check first here either;
+        // this at least still stops an oversized blob from reaching the
+        // gutter.
+        if let Some(limit) = self.max_diff_base_size() {
+            let size = data.len() as u64;
+            if size > limit {
+                return Err(TooLarge { size, limit }.into());
+            }
+        }
+
+        Ok(data)
+    }
+
+    /// Uses hg's `REV~N` revset shorthand, which already walks first
+    /// parents `n` times from `.` (the working parent) the same way
+    /// [`get_diff_base_ancestor`](DiffProvider::get_diff_base_ancestor)
+    /// asks for.
+    fn get_diff_base_ancestor(&self, file: &Path, n: u32) -> Result<Vec<u8>> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+        let rev = format!(".~{n}");
+        if !self.backend.is_tracked_at(&repo_root, &rev, &rel_path) {
+            bail!("file did not exist {n} commits back");
+        }
+        self.backend.cat(&repo_root, &rev, &rel_path)
+    }
+
+    /// Uses hg's phase boundary (`last(public() & ::.)`) as the "shared and
+    /// effectively immutable" line, the same idea
+    /// [`crate::Git::get_diff_base_public`] approximates via remote-tracking
+    /// branch reachability since git has no phase concept of its own. Falls
+    /// back to an empty base when nothing reachable from the working parent
+    /// has been marked public yet.
+    fn get_diff_base_public(&self, file: &Path) -> Result<Vec<u8>> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+        let Some(public_rev) = self.backend.nearest_public_ancestor(&repo_root, ".")? else {
+            return Ok(Vec::new());
+        };
+        if !self.backend.is_tracked_at(&repo_root, &public_rev, &rel_path) {
+            return Ok(Vec::new());
+        }
+        self.backend.cat(&repo_root, &public_rev, &rel_path)
+    }
+
+    /// Resolves `ref_name` (a branch, bookmark, or tag) the same way `hg`'s
+    /// own revset parser already does, then cats the file at that
+    /// revision - unlike git's `resolve_rev`, there's no separate
+    /// name-to-revision lookup step to write, since `-r <ref_name>` accepts
+    /// a symbolic name directly.
+    fn get_file_on_ref(&self, file: &Path, ref_name: &str) -> Result<Vec<u8>> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+        if !self.backend.is_tracked_at(&repo_root, ref_name, &rel_path) {
+            bail!("ref {ref_name} not found, or does not contain {}", rel_path.display());
+        }
+        self.backend.cat(&repo_root, ref_name, &rel_path)
+    }
+
+    fn get_current_head_name(&self, file: &Path) -> Result<Arc<ArcSwap<Box<str>>>> {
+        let (repo_root, _) = adapter::locate(file)?;
+        let name = self.backend.branch(&repo_root)?;
+        Ok(Arc::new(ArcSwap::from_pointee(name.into_boxed_str())))
+    }
+
+    fn for_each_changed_file(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        f: &mut dyn FnMut(Result<FileChange>),
+    ) -> Result<()> {
+        let (repo_root, _) = adapter::locate(cwd)?;
+        for change in self.backend.status(&repo_root, None, None, config)? {
+            f(Ok(change));
+        }
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "hg"
+    }
+
+    /// No `staged_diff`: `hg` has no index/staging area to read a base
+    /// from, unlike git's `get_diff_base_source`/`DiffBaseSource::Index`
+    /// override.
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            blame: true,
+            history: true,
+            staged_diff: false,
+        }
+    }
+
+    fn changed_files_between(
+        &self,
+        file: &Path,
+        rev_a: &str,
+        rev_b: &str,
+    ) -> Result<Vec<FileChange>> {
+        let (repo_root, _) = adapter::locate(file)?;
+        self.backend
+            .status(&repo_root, Some(rev_a), Some(rev_b), &StatusConfig::default())
+    }
+
+    fn file_mode(&self, file: &Path) -> Result<FileMode> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+        self.backend.file_mode(&repo_root, &rel_path)
+    }
+
+    /// Content-identity check: compares `a` and `b`'s tracked contents
+    /// directly rather than parsing filelog copy metadata, which `hg`'s CLI
+    /// has no stable plumbing command for (see the module doc comment on
+    /// why this crate avoids depending on `hg`'s own Rust crates).
+    fn same_tracked_source(&self, a: &Path, b: &Path) -> Result<bool> {
+        let (repo_root, rel_a) = adapter::locate(a)?;
+        let Ok(rel_b) = b.strip_prefix(&repo_root) else {
+            // Not in the same repo: can't be the same tracked source.
+            return Ok(false);
+        };
+
+        match (
+            self.backend.cat(&repo_root, ".", &rel_a),
+            self.backend.cat(&repo_root, ".", rel_b),
+        ) {
+            (Ok(content_a), Ok(content_b)) => Ok(content_a == content_b),
+            _ => Ok(false),
+        }
+    }
+
+    /// Resolves `file`'s repo root once (this backend shells out per call
+    /// rather than holding an open repository handle, so that resolution is
+    /// the amortizable part) and reuses it for the branch name and status
+    /// walk, instead of the default implementation's one-resolve-per-method
+    /// composition.
+    fn repo_snapshot(&self, file: &Path) -> Result<RepoSnapshot> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+
+        let name = self.backend.branch(&repo_root)?;
+        let head_name = Arc::new(ArcSwap::from_pointee(name.into_boxed_str()));
+
+        let mut counts = ChangeCounts::default();
+        let mut file_dirty = false;
+        for change in self
+            .backend
+            .status(&repo_root, None, None, &StatusConfig::default())?
+        {
+            if change.path == rel_path {
+                file_dirty = true;
+            }
+            counts.record(change.kind);
+        }
+
+        Ok(RepoSnapshot {
+            head_name,
+            counts,
+            file_dirty,
+        })
+    }
+
+    /// Reuses [`Hg::unresolved_files`]'s mergestate read, which is already
+    /// the cheap "no merge in progress" fast path (`hg resolve --list`
+    /// exits non-zero with nothing to report rather than forcing a full
+    /// status walk), so there's no separate conflict-marker scan needed
+    /// here the way `git`'s override has to do.
+    fn has_conflicts(&self, file: &Path) -> Result<bool> {
+        Ok(!self.unresolved_files(file)?.is_empty())
+    }
+
+    fn current_commit_summary(&self, file: &Path, max_len: Option<usize>) -> Result<String> {
+        let (repo_root, _) = adapter::locate(file)?;
+        let summary = self.backend.commit_summary(&repo_root, ".")?;
+        Ok(crate::truncate_with_ellipsis(&summary, max_len))
+    }
+
+    fn head_extra(&self, file: &Path, key: &str) -> Result<Option<String>> {
+        let (repo_root, _) = adapter::locate(file)?;
+        self.backend.extra(&repo_root, ".", key)
+    }
+
+    /// Annotates the whole file via [`Backend::blame`], then slices
+    /// `lines` out of that rather than asking `hg` to limit the range
+    /// itself, since annotate has no stable flag for that.
+    fn blame_range(&self, file: &Path, lines: Range<usize>) -> Result<Vec<BlameInfo>> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+        let nodes = self.backend.blame(&repo_root, &rel_path)?;
+        if lines.end > nodes.len() {
+            bail!(
+                "requested lines {}..{} extend past the file's {} lines",
+                lines.start,
+                lines.end,
+                nodes.len()
+            );
+        }
+
+        let mut summaries: HashMap<&str, String> = HashMap::new();
+        Ok(nodes[lines.clone()]
+            .iter()
+            .enumerate()
+            .map(|(i, commit)| {
+                let summary = summaries.entry(commit).or_insert_with(|| {
+                    self.backend
+                        .commit_summary(&repo_root, commit)
+                        .unwrap_or_default()
+                });
+                BlameInfo {
+                    line: lines.start + i,
+                    commit: commit.clone(),
+                    summary: summary.clone(),
+                }
+            })
+            .collect())
+    }
+
+    /// Like [`Self::blame_range`], but for a scattered set of lines instead
+    /// of a contiguous range - still just one [`Backend::blame`] annotate
+    /// pass, sliced differently on the way out.
+    fn blame_lines(&self, file: &Path, lines: &[u32]) -> Result<HashMap<u32, BlameInfo>> {
+        let (repo_root, rel_path) = adapter::locate(file)?;
+        let nodes = self.backend.blame(&repo_root, &rel_path)?;
+
+        let mut summaries: HashMap<&str, String> = HashMap::new();
+        lines
+            .iter()
+            .map(|&line| {
+                let commit = nodes.get(line as usize).with_context(|| {
+                    format!(
+                        "requested line {line} extends past the file's {} lines",
+                        nodes.len()
+                    )
+                })?;
+                let summary = summaries.entry(commit).or_insert_with(|| {
+                    self.backend
+                        .commit_summary(&repo_root, commit)
+                        .unwrap_or_default()
+                });
+                Ok((
+                    line,
+                    BlameInfo {
+                        line: line as usize,
+                        commit: commit.clone(),
+                        summary: summary.clone(),
+                    },
+                ))
+            })
+            .collect()
+    }
+
+    fn working_directory(&self, file: &Path) -> Result<PathBuf> {
+        let (repo_root, _) = adapter::locate(file)?;
+        Ok(repo_root)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn capabilities_reports_no_staged_diff_support() {
+        let caps = Hg::<CliBackend>::default().capabilities();
+        assert!(caps.blame);
+        assert!(caps.history);
+        assert!(!caps.staged_diff);
+    }
+
+    #[test]
+    fn largefile_standin_path_is_under_hglf() {
+        assert_eq!(
+            largefile_standin_path(Path::new("assets/video.mp4")),
+            Path::new(".hglf/assets/video.mp4")
+        );
+    }
+
+    #[test]
+    fn resolve_largefile_reads_from_the_local_cache() {
+        let repo = tempfile::tempdir().unwrap();
+        let hash = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        let cache_dir = repo.path().join(".hg").join("largefiles");
+        std::fs::create_dir_all(&cache_dir).unwrap();
+        std::fs::write(cache_dir.join(hash), b"real content").unwrap();
+
+        let data = resolve_largefile(repo.path(), hash).unwrap();
+        assert_eq!(data, b"real content");
+    }
+
+    #[test]
+    fn resolve_largefile_reports_when_the_object_is_missing_from_the_cache() {
+        let repo = tempfile::tempdir().unwrap();
+        let err = resolve_largefile(repo.path(), "deadbeef").unwrap_err();
+        let not_available = err.downcast_ref::<LargefileNotAvailable>().unwrap();
+        assert_eq!(not_available.hash, "deadbeef");
+    }
+}
@@ -0,0 +1,667 @@
+//! The one place [`super::Hg`] is allowed to know the shape of `hg`'s
+//! output. Swapping this for a real `hg`-crate-backed implementation later
+//! (once one exists with a stable enough API to depend on) only requires
+//! a new [`Backend`] impl; [`super::Hg`]'s `DiffProvider` methods never
+//! change.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{bail, Context, Result};
+
+use crate::{ChangeKind, FileChange, FileMode, StatusConfig};
+
+pub(super) trait Backend {
+    /// Returns `path`'s contents (relative to `repo_root`) as recorded at
+    /// `rev`.
+    fn cat(&self, repo_root: &Path, rev: &str, path: &Path) -> Result<Vec<u8>>;
+
+    /// The active bookmark/branch name for `repo_root`.
+    fn branch(&self, repo_root: &Path) -> Result<String>;
+
+    /// Changed files in `repo_root`. With both revisions `None`, reports
+    /// working-directory status against the working parent (`hg status`);
+    /// with both given, reports status between them (`hg status --rev a
+    /// --rev b`). `config` adds `--unknown`/`--ignored`/`--clean` as
+    /// requested; these only apply to a working-directory status, they have
+    /// no meaning (and aren't passed) for a revision-to-revision comparison.
+    /// `config.subdir` narrows the scope in both cases. `--copies` is always
+    /// passed, regardless of `config.list_copies` - see
+    /// [`parse_status_output`] for why.
+    fn status(
+        &self,
+        repo_root: &Path,
+        rev_a: Option<&str>,
+        rev_b: Option<&str>,
+        config: &StatusConfig,
+    ) -> Result<Vec<FileChange>>;
+
+    /// The tracked mode of `path` (relative to `repo_root`) in the working
+    /// copy. Errors if `path` isn't tracked.
+    fn file_mode(&self, repo_root: &Path, path: &Path) -> Result<FileMode>;
+
+    /// Paths recorded as unresolved (`U`) in `repo_root`'s mergestate. Empty
+    /// when there's no merge in progress.
+    fn unresolved_files(&self, repo_root: &Path) -> Result<Vec<PathBuf>>;
+
+    /// The first line of the changelog entry's commit message at `rev`.
+    /// Errors (via [`run`]'s non-zero-exit handling) if `rev` doesn't
+    /// resolve, which is what happens when the repository has no commits
+    /// yet and `rev` is `.`.
+    fn commit_summary(&self, repo_root: &Path, rev: &str) -> Result<String>;
+
+    /// The commit id that last touched each line of `path` (relative to
+    /// `repo_root`) as of the working parent, one entry per line, in file
+    /// order. Annotate has no stable line-range flag to delegate a
+    /// sub-range to, so [`super::Hg::blame_range`] slices the range it
+    /// actually wants out of this full result instead.
+    fn blame(&self, repo_root: &Path, path: &Path) -> Result<Vec<String>>;
+
+    /// Whether `path` is tracked at `rev`. Consistent with
+    /// [`Backend::file_mode`]'s own tracked check, any `hg files` failure
+    /// (revision doesn't resolve, path was never added, ...) is treated
+    /// uniformly as "not tracked" rather than trying to separate the causes.
+    fn is_tracked_at(&self, repo_root: &Path, rev: &str, path: &Path) -> bool;
+
+    /// The phase (`public`, `draft`, or `secret`) of `rev`.
+    fn phase(&self, repo_root: &Path, rev: &str) -> Result<String>;
+
+    /// `rev`'s nearest ancestor (including itself) in the `public` phase,
+    /// found via the revset `last(public() & ::rev)` - hg's phase boundary
+    /// is the closest analogue to the remote-tracking-branch reachability
+    /// [`crate::Git::get_diff_base_public`] uses for the same "everything
+    /// not yet shared" idea. `None` if no ancestor of `rev` has been marked
+    /// public at all (a repo that's still entirely draft/secret).
+    fn nearest_public_ancestor(&self, repo_root: &Path, rev: &str) -> Result<Option<String>>;
+
+    /// Paths the dirstate can't yet confirm clean or modified without a
+    /// content check - recorded with an `unset` mtime (`hg debugstate`'s
+    /// own marker for an entry last touched in the same second it was
+    /// written), which hg itself would otherwise have to hash to tell apart
+    /// from a real modification. Read directly from the dirstate rather
+    /// than derived from [`Backend::status`]'s output, so a caller can
+    /// cheaply check "is this entry ambiguous" without forcing the content
+    /// comparison [`StatusConfig::verify_unsure`] exists to make optional.
+    fn unsure_files(&self, repo_root: &Path) -> Result<HashSet<PathBuf>>;
+
+    /// The working directory's parent commit ids. One entry normally; two
+    /// when there's an uncommitted merge in progress.
+    fn working_parents(&self, repo_root: &Path) -> Result<Vec<String>>;
+
+    /// One named entry from `rev`'s changeset extras (`hg log -T
+    /// "{extras.<key>}"`), e.g. `source` on a transplanted or converted
+    /// commit. `None` if `rev` has no extra recorded under `key` - hg's
+    /// template engine renders a missing key as an empty string rather
+    /// than erroring, so that's read back as absence rather than an error.
+    fn extra(&self, repo_root: &Path, rev: &str, key: &str) -> Result<Option<String>>;
+
+    /// The nearest tag reachable by walking ancestors back from `rev`, and
+    /// how many commits separate them - hg's own `{latesttag}`/
+    /// `{latesttagdistance}` template keywords, the same computation `hg
+    /// log`'s default templates already rely on, and hg's closest
+    /// equivalent to `git describe`. Reads only tags already committed to
+    /// history (no remote is ever consulted). `None` if no tag is reachable
+    /// from `rev` at all - hg renders `{latesttag}` as `"null"` in that case.
+    fn nearest_tag(&self, repo_root: &Path, rev: &str) -> Result<Option<(String, u32)>>;
+}
+
+pub(super) struct CliBackend;
+
+impl Backend for CliBackend {
+    fn cat(&self, repo_root: &Path, rev: &str, path: &Path) -> Result<Vec<u8>> {
+        run(repo_root, &["cat", "-r", rev, &path.to_string_lossy()])
+    }
+
+    fn branch(&self, repo_root: &Path) -> Result<String> {
+        let output = run(repo_root, &["branch"])?;
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    }
+
+    fn status(
+        &self,
+        repo_root: &Path,
+        rev_a: Option<&str>,
+        rev_b: Option<&str>,
+        config: &StatusConfig,
+    ) -> Result<Vec<FileChange>> {
+        let mut args = vec!["status".to_string()];
+        let is_working_copy_status = rev_a.is_none() && rev_b.is_none();
+        if let (Some(a), Some(b)) = (rev_a, rev_b) {
+            args.extend(["--rev".to_string(), a.to_string(), "--rev".to_string(), b.to_string()]);
+        }
+        if is_working_copy_status {
+            if config.list_unknown {
+                args.push("--unknown".to_string());
+            }
+            if config.list_ignored {
+                args.push("--ignored".to_string());
+            }
+            if config.list_clean {
+                args.push("--clean".to_string());
+            }
+        }
+        // Always asked for, even when `config.list_copies` is unset: without
+        // it there'd be no way to tell a rename's target/source pair apart
+        // from an unrelated add + delete, so `parse_status_output` couldn't
+        // dedupe them. `list_copies` still controls whether a copy (as
+        // opposed to a rename) gets surfaced as `ChangeKind::Copied`.
+        args.push("--copies".to_string());
+        if let Some(subdir) = &config.subdir {
+            args.push(subdir.to_string_lossy().into_owned());
+        }
+
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        let output = run(repo_root, &arg_refs)?;
+        let mut changes = parse_status_output(
+            &String::from_utf8_lossy(&output),
+            repo_root,
+            config.list_copies,
+        );
+
+        if is_working_copy_status {
+            let unsure = self.unsure_files(repo_root)?;
+            changes.retain_mut(|change| {
+                if change.kind != ChangeKind::Modified || !unsure.contains(&change.path) {
+                    return true;
+                }
+                if !config.verify_unsure {
+                    change.kind = ChangeKind::Conflict;
+                    return true;
+                }
+                let current = std::fs::read(&change.abs_path).unwrap_or_default();
+                let committed = self.cat(repo_root, ".", &change.path).unwrap_or_default();
+                if current == committed {
+                    change.kind = ChangeKind::Clean;
+                    config.list_clean
+                } else {
+                    change.kind = ChangeKind::Modified;
+                    true
+                }
+            });
+        }
+
+        Ok(changes)
+    }
+
+    fn file_mode(&self, repo_root: &Path, path: &Path) -> Result<FileMode> {
+        // `hg files` is the plumbing command for "is this path tracked", so
+        // it's used just to confirm that rather than to read the mode
+        // itself: hg mirrors a tracked file's executable flag and symlink-
+        // ness onto the working copy's own filesystem entry, so that's a
+        // simpler and more reliable source for the actual bits than parsing
+        // another `hg` subcommand's output.
+        run(repo_root, &["files", &path.to_string_lossy()])
+            .with_context(|| format!("{} is not tracked", path.display()))?;
+
+        let metadata = std::fs::symlink_metadata(repo_root.join(path))
+            .with_context(|| format!("failed to read metadata for {}", path.display()))?;
+        if metadata.is_symlink() {
+            return Ok(FileMode::Symlink);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if metadata.permissions().mode() & 0o111 != 0 {
+                return Ok(FileMode::Executable);
+            }
+        }
+        Ok(FileMode::Regular)
+    }
+
+    fn unresolved_files(&self, repo_root: &Path) -> Result<Vec<PathBuf>> {
+        // `hg resolve --list` exits with a non-zero status when there's no
+        // merge in progress, rather than printing an empty list; that's not
+        // a real failure here, it just means "nothing unresolved".
+        let output = match run(repo_root, &["resolve", "--list"]) {
+            Ok(output) => output,
+            Err(_) => return Ok(Vec::new()),
+        };
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .filter_map(parse_resolve_line)
+            .collect())
+    }
+
+    fn commit_summary(&self, repo_root: &Path, rev: &str) -> Result<String> {
+        let output = run(repo_root, &["log", "-r", rev, "--template", "{desc|firstline}"])?;
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    }
+
+    fn blame(&self, repo_root: &Path, path: &Path) -> Result<Vec<String>> {
+        let output = run(
+            repo_root,
+            &[
+                "annotate",
+                "-r",
+                ".",
+                "-T",
+                "{node}\n",
+                &path.to_string_lossy(),
+            ],
+        )?;
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .collect())
+    }
+
+    fn is_tracked_at(&self, repo_root: &Path, rev: &str, path: &Path) -> bool {
+        run(repo_root, &["files", "-r", rev, &path.to_string_lossy()]).is_ok()
+    }
+
+    fn phase(&self, repo_root: &Path, rev: &str) -> Result<String> {
+        let output = run(repo_root, &["log", "-r", rev, "--template", "{phase}"])?;
+        Ok(String::from_utf8_lossy(&output).trim().to_string())
+    }
+
+    fn unsure_files(&self, repo_root: &Path) -> Result<HashSet<PathBuf>> {
+        let output = run(repo_root, &["debugstate", "--no-dates"])?;
+        Ok(parse_debugstate_output(&String::from_utf8_lossy(&output)))
+    }
+
+    fn nearest_public_ancestor(&self, repo_root: &Path, rev: &str) -> Result<Option<String>> {
+        let output = run(
+            repo_root,
+            &[
+                "log",
+                "-r",
+                &format!("last(public() & ::{rev})"),
+                "--template",
+                "{node}",
+            ],
+        )?;
+        let node = String::from_utf8_lossy(&output).trim().to_string();
+        Ok((!node.is_empty()).then_some(node))
+    }
+
+    fn working_parents(&self, repo_root: &Path) -> Result<Vec<String>> {
+        let output = run(
+            repo_root,
+            &["log", "-r", "parents()", "--template", "{node}\n"],
+        )?;
+        Ok(String::from_utf8_lossy(&output)
+            .lines()
+            .map(|line| line.trim().to_string())
+            .collect())
+    }
+
+    fn extra(&self, repo_root: &Path, rev: &str, key: &str) -> Result<Option<String>> {
+        let output = run(
+            repo_root,
+            &["log", "-r", rev, "--template", &format!("{{extras.{key}}}")],
+        )?;
+        let value = String::from_utf8_lossy(&output).trim().to_string();
+        Ok((!value.is_empty()).then_some(value))
+    }
+
+    fn nearest_tag(&self, repo_root: &Path, rev: &str) -> Result<Option<(String, u32)>> {
+        let output = run(
+            repo_root,
+            &["log", "-r", rev, "--template", "{latesttag}\t{latesttagdistance}"],
+        )?;
+        let text = String::from_utf8_lossy(&output);
+        let (tag, distance) = text.trim().split_once('\t').context("malformed tag template output")?;
+        if tag == "null" {
+            return Ok(None);
+        }
+        let distance: u32 = distance.parse().context("malformed tag distance")?;
+        Ok(Some((tag.to_string(), distance)))
+    }
+}
+
+/// Parses one line of `hg status`'s porcelain output (`"M some/path"`) into
+/// a [`FileChange`]. `?`/`I`/`C` (untracked/ignored/clean) lines only appear
+/// when the corresponding [`StatusConfig`] flag asked `hg status` to report
+/// them; an untracked file is reported as [`ChangeKind::Added`], matching
+/// how `git`'s provider treats the same situation. Never reports
+/// [`ChangeKind::Renamed`]/[`ChangeKind::Copied`] on its own - see
+/// [`parse_status_output`], which re-derives those from the copy-source
+/// lines this function doesn't know how to read.
+fn parse_status_line(line: &str, repo_root: &Path) -> Option<FileChange> {
+    let (code, rel_path) = line.split_once(' ')?;
+    // hg's porcelain status codes: M(odified) A(dded) R(emoved) C(lean)
+    // !(missing) ?(untracked) I(gnored).
+    let kind = match code {
+        "M" => ChangeKind::Modified,
+        "A" | "?" => ChangeKind::Added,
+        "R" | "!" => ChangeKind::Deleted,
+        "I" => ChangeKind::Ignored,
+        "C" => ChangeKind::Clean,
+        _ => return None,
+    };
+    let path = PathBuf::from(rel_path);
+    Some(FileChange {
+        abs_path: repo_root.join(&path),
+        path,
+        kind,
+        from_path: None,
+    })
+}
+
+/// Parses the full output of an `hg status --copies` invocation, pairing
+/// each `A`(dded) entry with the `  <old path>` copy-source line `--copies`
+/// prints directly under it (if any), and reclassifying the pair:
+///
+/// - If the old path also appears as an `R`(emoved)/`!`(missing) entry in
+///   the same output, the add/remove pair is really one rename: the added
+///   entry becomes [`ChangeKind::Renamed`] with `from_path` set, and the
+///   standalone [`ChangeKind::Deleted`] entry for the old path is dropped
+///   rather than double-counting the move as both a deletion and a rename.
+/// - Otherwise the old path is still present (a copy, not a move). Only
+///   reported as [`ChangeKind::Copied`] when `list_copies` is set, matching
+///   [`StatusConfig::list_copies`]'s contract; otherwise left as a plain
+///   `Added`, the same as before this function existed.
+fn parse_status_output(output: &str, repo_root: &Path, list_copies: bool) -> Vec<FileChange> {
+    let mut changes = Vec::new();
+    let mut copy_source_of: HashMap<PathBuf, PathBuf> = HashMap::new();
+
+    for line in output.lines() {
+        if let Some(old_path) = line.strip_prefix("  ") {
+            if let Some(last) = changes.last() {
+                copy_source_of.insert(last.path.clone(), PathBuf::from(old_path));
+            }
+            continue;
+        }
+        if let Some(change) = parse_status_line(line, repo_root) {
+            changes.push(change);
+        }
+    }
+
+    let deleted: HashSet<&Path> = changes
+        .iter()
+        .filter(|change| change.kind == ChangeKind::Deleted)
+        .map(|change| change.path.as_path())
+        .collect();
+
+    let mut renamed_sources = HashSet::new();
+    for change in &mut changes {
+        if change.kind != ChangeKind::Added {
+            continue;
+        }
+        let Some(from_path) = copy_source_of.get(&change.path) else {
+            continue;
+        };
+        if deleted.contains(from_path.as_path()) {
+            renamed_sources.insert(from_path.clone());
+            change.kind = ChangeKind::Renamed;
+            change.from_path = Some(from_path.clone());
+        } else if list_copies {
+            change.kind = ChangeKind::Copied;
+            change.from_path = Some(from_path.clone());
+        }
+    }
+
+    changes.retain(|change| {
+        !(change.kind == ChangeKind::Deleted && renamed_sources.contains(&change.path))
+    });
+    changes
+}
+
+/// Parses one line of `hg resolve --list`'s porcelain output (`"U
+/// some/path"`), keeping only unresolved (`U`) entries - resolved (`R`)
+/// files, including ones later modified again, are not conflicts anymore.
+fn parse_resolve_line(line: &str) -> Option<PathBuf> {
+    let (code, rel_path) = line.split_once(' ')?;
+    (code == "U").then(|| PathBuf::from(rel_path))
+}
+
+/// Parses `hg debugstate --no-dates`'s output (`"<state> <mode> <size>
+/// <mtime> <path>"`, one line per dirstate entry), collecting paths whose
+/// mtime field reads `unset` - the dirstate's own marker for an entry it
+/// can't yet confirm clean or modified without hashing its contents.
+fn parse_debugstate_output(output: &str) -> HashSet<PathBuf> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _state = fields.next()?;
+            let _mode = fields.next()?;
+            let _size = fields.next()?;
+            let mtime = fields.next()?;
+            let path = fields.next()?;
+            (mtime == "unset").then(|| PathBuf::from(path))
+        })
+        .collect()
+}
+
+fn run(repo_root: &Path, args: &[&str]) -> Result<Vec<u8>> {
+    let output = Command::new("hg")
+        .arg("--cwd")
+        .arg(repo_root)
+        .args(args)
+        .output()
+        .context("failed to run `hg`")?;
+    if !output.status.success() {
+        bail!(
+            "`hg {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(output.stdout)
+}
+
+/// Finds the repository root and `file`'s path relative to it, by walking
+/// up for the nearest `.hg` directory. The working copy itself is always
+/// `dir` (that's where the tracked files and the dirstate actually live),
+/// even when `.hg/sharedpath` points the real store somewhere else -
+/// [`resolve_shared_store`] only confirms that indirection is reachable,
+/// it doesn't change what's returned.
+pub(super) fn locate(file: &Path) -> Result<(PathBuf, PathBuf)> {
+    let start = if file.is_dir() {
+        file
+    } else {
+        file.parent().context("file has no parent directory")?
+    };
+    let mut dir = start;
+    loop {
+        let hg_dir = dir.join(".hg");
+        if hg_dir.is_dir() {
+            resolve_shared_store(&hg_dir)?;
+            let rel = file.strip_prefix(dir).unwrap_or(file);
+            return Ok((dir.to_path_buf(), rel.to_path_buf()));
+        }
+        dir = match dir.parent() {
+            Some(parent) => parent,
+            None => bail!("{} is not inside an hg repository", file.display()),
+        };
+    }
+}
+
+/// Resolves `<hg_dir>/sharedpath`, if present: an `hg share`-created
+/// checkout's `.hg` holds only its own working-copy state, while the real
+/// store lives at the path recorded there (relative to `hg_dir` itself
+/// when the source was shared with `--relative`, absolute otherwise). Bails
+/// if the path it points at doesn't exist - every `hg` command would fail
+/// on this checkout anyway, just with a less specific error than this one.
+fn resolve_shared_store(hg_dir: &Path) -> Result<()> {
+    let sharedpath_file = hg_dir.join("sharedpath");
+    let Ok(raw) = std::fs::read_to_string(&sharedpath_file) else {
+        // No `sharedpath` file: an ordinary, non-shared repository.
+        return Ok(());
+    };
+    let shared = PathBuf::from(raw.trim());
+    let shared = if shared.is_relative() {
+        hg_dir.join(shared)
+    } else {
+        shared
+    };
+    if !shared.is_dir() {
+        bail!(
+            "{} points at a shared store that no longer exists: {}",
+            sharedpath_file.display(),
+            shared.display()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_known_status_codes() {
+        let root = Path::new("/repo");
+        assert_eq!(
+            parse_status_line("M src/lib.rs", root).unwrap(),
+            FileChange {
+                path: PathBuf::from("src/lib.rs"),
+                abs_path: PathBuf::from("/repo/src/lib.rs"),
+                kind: ChangeKind::Modified,
+                from_path: None,
+            }
+        );
+        assert_eq!(
+            parse_status_line("A new.rs", root).unwrap().kind,
+            ChangeKind::Added
+        );
+        assert_eq!(
+            parse_status_line("R removed.rs", root).unwrap().kind,
+            ChangeKind::Deleted
+        );
+        assert_eq!(
+            parse_status_line("! missing.rs", root).unwrap().kind,
+            ChangeKind::Deleted
+        );
+    }
+
+    #[test]
+    fn parses_clean_untracked_and_ignored_entries() {
+        let root = Path::new("/repo");
+        assert_eq!(
+            parse_status_line("C clean.rs", root).unwrap().kind,
+            ChangeKind::Clean
+        );
+        assert_eq!(
+            parse_status_line("? untracked.rs", root).unwrap().kind,
+            ChangeKind::Added
+        );
+        assert_eq!(
+            parse_status_line("I ignored.rs", root).unwrap().kind,
+            ChangeKind::Ignored
+        );
+    }
+
+    #[test]
+    fn rejects_unrecognized_status_lines() {
+        let root = Path::new("/repo");
+        assert!(parse_status_line("X unknown.rs", root).is_none());
+        assert!(parse_status_line("", root).is_none());
+    }
+
+    #[test]
+    fn parse_status_output_collapses_a_tracked_rename_into_one_renamed_entry() {
+        let root = Path::new("/repo");
+        let changes = parse_status_output("A new.rs\n  old.rs\nR old.rs\n", root, false);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, PathBuf::from("new.rs"));
+        assert_eq!(changes[0].kind, ChangeKind::Renamed);
+        assert_eq!(changes[0].from_path, Some(PathBuf::from("old.rs")));
+        assert!(!changes.iter().any(|change| change.kind == ChangeKind::Deleted));
+    }
+
+    #[test]
+    fn parse_status_output_leaves_a_genuine_copy_as_added_unless_list_copies_is_set() {
+        let root = Path::new("/repo");
+        let output = "A new.rs\n  old.rs\nC old.rs\n";
+
+        let changes = parse_status_output(output, root, false);
+        let added = changes
+            .iter()
+            .find(|change| change.path == Path::new("new.rs"))
+            .unwrap();
+        assert_eq!(added.kind, ChangeKind::Added);
+        assert_eq!(added.from_path, None);
+
+        let changes = parse_status_output(output, root, true);
+        let copied = changes
+            .iter()
+            .find(|change| change.path == Path::new("new.rs"))
+            .unwrap();
+        assert_eq!(copied.kind, ChangeKind::Copied);
+        assert_eq!(copied.from_path, Some(PathBuf::from("old.rs")));
+    }
+
+    #[test]
+    fn parse_resolve_line_keeps_only_unresolved() {
+        assert_eq!(
+            parse_resolve_line("U conflicted.rs"),
+            Some(PathBuf::from("conflicted.rs"))
+        );
+        assert!(parse_resolve_line("R resolved.rs").is_none());
+        assert!(parse_resolve_line("").is_none());
+    }
+
+    #[test]
+    fn parse_debugstate_output_flags_unset_mtimes_as_unsure() {
+        let output = "\
+n 644          12 1700000000 clean.rs
+n 644           3 unset fresh.rs
+";
+        assert_eq!(
+            parse_debugstate_output(output),
+            HashSet::from([PathBuf::from("fresh.rs")])
+        );
+    }
+
+    #[test]
+    fn locate_finds_the_nearest_hg_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        std::fs::create_dir(tmp.path().join(".hg")).unwrap();
+        std::fs::create_dir(tmp.path().join("src")).unwrap();
+        let file = tmp.path().join("src").join("lib.rs");
+        std::fs::write(&file, "").unwrap();
+
+        let (repo_root, rel_path) = locate(&file).unwrap();
+        assert_eq!(repo_root, tmp.path());
+        assert_eq!(rel_path, Path::new("src/lib.rs"));
+    }
+
+    #[test]
+    fn locate_fails_outside_a_repository() {
+        let tmp = tempfile::tempdir().unwrap();
+        let file = tmp.path().join("lib.rs");
+        std::fs::write(&file, "").unwrap();
+
+        assert!(locate(&file).is_err());
+    }
+
+    #[test]
+    fn locate_resolves_through_a_sharedpath_indirection() {
+        let tmp = tempfile::tempdir().unwrap();
+        let source_hg = tmp.path().join("source").join(".hg");
+        std::fs::create_dir_all(&source_hg).unwrap();
+
+        let checkout_hg = tmp.path().join("checkout").join(".hg");
+        std::fs::create_dir_all(&checkout_hg).unwrap();
+        std::fs::write(checkout_hg.join("sharedpath"), source_hg.to_str().unwrap()).unwrap();
+
+        let file = checkout_hg.parent().unwrap().join("src").join("lib.rs");
+        std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+        std::fs::write(&file, "").unwrap();
+
+        let (repo_root, rel_path) = locate(&file).unwrap();
+        assert_eq!(repo_root, checkout_hg.parent().unwrap());
+        assert_eq!(rel_path, Path::new("src/lib.rs"));
+    }
+
+    #[test]
+    fn locate_fails_when_sharedpath_points_at_a_missing_store() {
+        let tmp = tempfile::tempdir().unwrap();
+        let checkout_hg = tmp.path().join("checkout").join(".hg");
+        std::fs::create_dir_all(&checkout_hg).unwrap();
+        std::fs::write(
+            checkout_hg.join("sharedpath"),
+            tmp.path().join("gone-source").join(".hg").to_str().unwrap(),
+        )
+        .unwrap();
+
+        let file = checkout_hg.parent().unwrap().join("lib.rs");
+        std::fs::write(&file, "").unwrap();
+
+        assert!(locate(&file).is_err());
+    }
+}
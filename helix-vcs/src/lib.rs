@@ -1,6 +1,17 @@
-use anyhow::{bail, Result};
+use anyhow::{bail, Context, Result};
 use arc_swap::ArcSwap;
-use std::{path::Path, sync::Arc};
+use helix_core::Rope;
+use std::{
+    collections::HashMap,
+    hash::{Hash, Hasher},
+    io::{Cursor, Read},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
 #[cfg(feature = "git")]
 pub use git::Git;
@@ -9,18 +20,485 @@
 
 #[cfg(feature = "git")]
 mod git;
+#[cfg(feature = "hg")]
+mod hg;
 
+mod conflict;
 mod diff;
+#[cfg(feature = "git")]
+mod mailmap;
+mod status;
+
+#[cfg(feature = "git")]
+pub use git::invalidate_repo_cache;
+#[cfg(feature = "git")]
+pub use mailmap::Mailmap;
+#[cfg(feature = "hg")]
+pub use hg::Hg as HgProvider;
+#[cfg(feature = "hg")]
+pub use hg::LargefileNotAvailable;
 
-pub use diff::{DiffHandle, Hunk};
+pub use conflict::{resolve_conflicts, Side};
+pub use diff::{next_hunk_after, prev_hunk_before, ChangedLines, DiffHandle, Hunk};
+pub use status::{
+    BlameInfo, ChangeCounts, ChangeKind, ChangeSortKey, DiffBaseSource, FileChange, FileMode,
+    HeadState, MultiRootFileChange, ProviderCapabilities, RepoCaps, RepoSnapshot, StatusConfig,
+};
+
+/// The default for [`DiffProvider::max_diff_base_size`]: generous enough for
+/// any real source file, while still refusing to load a multi-gigabyte
+/// tracked blob into memory just to diff it.
+pub const DEFAULT_MAX_DIFF_BASE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Returned (as the `anyhow::Error` backing [`DiffProvider::get_diff_base`]'s
+/// `Err`, downcast with [`anyhow::Error::downcast_ref`]) when the diff base
+/// exceeds [`DiffProvider::max_diff_base_size`], so a caller like the gutter
+/// can tell "too big to diff" apart from a real failure and silently turn
+/// diffing off for that file instead of crashing trying to load it.
+#[derive(Debug, Clone, Copy)]
+pub struct TooLarge {
+    pub size: u64,
+    pub limit: u64,
+}
+
+impl std::fmt::Display for TooLarge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "diff base is {} bytes, over the {} byte limit",
+            self.size, self.limit
+        )
+    }
+}
+
+impl std::error::Error for TooLarge {}
+
+/// A cooperative cancellation flag shared across long-running VCS
+/// operations (a status walk, blame, a history lookup), checked
+/// periodically by the operation itself rather than interrupting it
+/// outright. [`CancelToken::none`] (also this type's [`Default`]) never
+/// trips, so a caller with no cancellation source of its own can pass it
+/// through unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// A token that never trips.
+    pub fn none() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Requests cancellation. Every clone of this token (they share the
+    /// same underlying flag) observes it on its next [`Self::is_cancelled`]
+    /// check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Returned (downcast via [`anyhow::Error::downcast_ref`]) when a
+/// `*_cancellable` operation bails because its [`CancelToken`] tripped
+/// partway through, so a caller can tell "the user navigated away" apart
+/// from a real failure and drop the partial result silently.
+#[derive(Debug, Clone, Copy)]
+pub struct Cancelled;
+
+impl std::fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "operation was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
 
 pub trait DiffProvider {
     /// Returns the data that a diff should be computed against
     /// if this provider is used.
     /// The data is returned as raw byte without any decoding or encoding performed
     /// to ensure all file encodings are handled correctly.
+    ///
+    /// Bails with [`TooLarge`] instead of the bytes if the base exceeds
+    /// [`Self::max_diff_base_size`].
     fn get_diff_base(&self, file: &Path) -> Result<Vec<u8>>;
     fn get_current_head_name(&self, file: &Path) -> Result<Arc<ArcSwap<Box<str>>>>;
+
+    /// The maximum size in bytes [`Self::get_diff_base`] will return before
+    /// bailing with [`TooLarge`] instead. `None` disables the guard
+    /// entirely. Defaults to [`DEFAULT_MAX_DIFF_BASE_SIZE`]; override to use
+    /// a different limit.
+    fn max_diff_base_size(&self) -> Option<u64> {
+        Some(DEFAULT_MAX_DIFF_BASE_SIZE)
+    }
+
+    /// Like [`Self::get_diff_base`], but exposed through a [`Read`] so large
+    /// files can be diffed without materializing the whole base in memory
+    /// upfront.
+    ///
+    /// The default implementation still eagerly loads the base and wraps it
+    /// in a [`Cursor`] - providers backed by a store that can stream blobs
+    /// directly (e.g. without fully inflating them first) should override
+    /// this instead.
+    fn get_diff_base_reader(&self, file: &Path) -> Result<Box<dyn Read>> {
+        Ok(Box::new(Cursor::new(self.get_diff_base(file)?)))
+    }
+
+    /// Cheaply checks whether `current_contents` differs from the diff base
+    /// without producing any hunks.
+    ///
+    /// The default implementation compares length and a hash of the bytes,
+    /// which is sufficient for both text and binary files.
+    fn is_file_dirty(&self, file: &Path, current_contents: &[u8]) -> Result<bool> {
+        let base = self.get_diff_base(file)?;
+        if base.len() != current_contents.len() {
+            return Ok(true);
+        }
+        Ok(hash_bytes(&base) != hash_bytes(current_contents))
+    }
+
+    /// Returns a named piece of review metadata attached to the working
+    /// parent, such as a Gerrit `Change-Id` trailer or an hg changeset extra.
+    /// Returns `Ok(None)` if the provider has no such metadata for `key`.
+    fn head_extra(&self, _file: &Path, _key: &str) -> Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Walks the tracked files below `cwd`, invoking `f` once for every file
+    /// that differs from the diff base (modified, added, or deleted), plus
+    /// whatever `config` additionally asks for (unknown, ignored, or clean
+    /// files; a narrower subdirectory scope). Errors encountered for
+    /// individual files are passed to `f` rather than aborting the whole
+    /// walk.
+    fn for_each_changed_file(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        f: &mut dyn FnMut(Result<FileChange>),
+    ) -> Result<()>;
+
+    /// Like [`Self::for_each_changed_file`], but also invokes `progress`
+    /// periodically (throttled, not on every file) with the number of
+    /// files scanned so far, so a UI can show a spinner/counter during a
+    /// walk over a huge repo. The default implementation just runs the
+    /// plain walk with no progress reporting, for providers that don't
+    /// override it.
+    fn for_each_changed_file_with_progress(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        f: &mut dyn FnMut(Result<FileChange>),
+        _progress: &mut dyn FnMut(usize),
+    ) -> Result<()> {
+        self.for_each_changed_file(cwd, config, f)
+    }
+
+    /// Reports whether `file`'s repository has a working directory at all
+    /// (a bare repo, or one opened from read-only media, doesn't) and
+    /// whether that working directory is writable. Consumers should check
+    /// this before attempting write-ish actions (staging a hunk, writing a
+    /// commit) so those fail up front with a clear reason instead of
+    /// partway through.
+    ///
+    /// The default implementation reports no working directory, which is
+    /// the safe assumption for a provider that doesn't override it.
+    fn repo_capabilities(&self, _file: &Path) -> Result<RepoCaps> {
+        Ok(RepoCaps {
+            has_work_dir: false,
+            writable: false,
+            provider: self.name(),
+        })
+    }
+
+    /// Short name of this provider, e.g. `"git"`. Used to populate
+    /// [`RepoCaps::provider`].
+    fn name(&self) -> &'static str {
+        "unknown"
+    }
+
+    /// Advertises which optional operations this provider implements, so a
+    /// caller can feature-detect up front (e.g. gray out "stage hunk" for a
+    /// provider with no index) instead of invoking the operation and
+    /// handling an "unsupported" error at the call site. The default
+    /// reports nothing supported, the safe assumption for a provider that
+    /// hasn't overridden this.
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities::default()
+    }
+
+    /// Returns the common-ancestor ("merge base") revision of `rev_a` and
+    /// `rev_b` for `file`'s repository, as a provider-specific revision
+    /// string. Feed the result to [`Self::get_diff_base_rev`] to diff
+    /// against a branch's merge base instead of its immediate working
+    /// parent - useful for "what changed on my branch" gutters.
+    fn merge_base(&self, _file: &Path, _rev_a: &str, _rev_b: &str) -> Result<String> {
+        bail!("this provider does not support merge-base lookups")
+    }
+
+    /// Like [`Self::get_diff_base`], but reads the file's contents at an
+    /// arbitrary revision rather than always the working parent.
+    fn get_diff_base_rev(&self, _file: &Path, _rev: &str) -> Result<Vec<u8>> {
+        bail!("this provider does not support revision-specific diff bases")
+    }
+
+    /// Like [`Self::get_diff_base_rev`], but `ref_name` is a symbolic name
+    /// (a branch, bookmark, or tag) rather than a bare revision string -
+    /// the friendlier entry point for "show this file as it is on
+    /// `main`". Errors clearly if `ref_name` doesn't resolve to anything.
+    ///
+    /// The default implementation just forwards to
+    /// [`Self::get_diff_base_rev`], since every provider in this crate
+    /// already accepts a symbolic name anywhere it accepts a revision
+    /// string; a provider whose revision strings are hash-only should
+    /// override this to resolve `ref_name` first.
+    fn get_file_on_ref(&self, file: &Path, ref_name: &str) -> Result<Vec<u8>> {
+        self.get_diff_base_rev(file, ref_name)
+    }
+
+    /// Like [`Self::get_diff_base`], but `n` first-parent ancestors back
+    /// from the working parent instead of the working parent itself - a
+    /// "step back through history" gutter. `n == 0` is the working parent
+    /// itself. Renames aren't followed, the same as [`Self::get_diff_base_rev`].
+    /// Errors if `file` didn't exist that far back (including because
+    /// history doesn't go back that far).
+    fn get_diff_base_ancestor(&self, _file: &Path, _n: u32) -> Result<Vec<u8>> {
+        bail!("this provider does not support ancestor diff bases")
+    }
+
+    /// Like [`Self::get_diff_base`], but against the latest ancestor of the
+    /// working parent considered "public" (shared outside this clone and
+    /// effectively immutable) rather than the working parent itself -
+    /// useful to see "everything not yet public" in one diff. Falls back to
+    /// an empty base (the whole file shown as new) when there's no public
+    /// ancestor at all.
+    fn get_diff_base_public(&self, _file: &Path) -> Result<Vec<u8>> {
+        bail!("this provider does not support a public-ancestor diff base")
+    }
+
+    /// Like [`Self::get_current_head_name`], but distinguishes an unborn
+    /// branch (named, but with no commits yet) and a detached checkout from
+    /// each other instead of collapsing both into a display string -
+    /// callers that only want to know "is there anything to diff against
+    /// yet" can match on [`HeadState::Unborn`] instead of treating it as a
+    /// hard error.
+    fn head_state(&self, _file: &Path) -> Result<HeadState> {
+        bail!("this provider does not support typed head state")
+    }
+
+    /// Like [`Self::get_diff_base`], but lets the caller choose whether to
+    /// read the working parent or the staging area - useful on a freshly
+    /// staged but uncommitted file, where [`Self::get_diff_base`] alone
+    /// would show the whole file as new. [`DiffBaseSource::Auto`] reads the
+    /// staged blob if the file has one, falling back to the working parent
+    /// otherwise.
+    ///
+    /// The default implementation only supports [`DiffBaseSource::Head`],
+    /// delegating to [`Self::get_diff_base`]; providers without a staging
+    /// area (or that haven't implemented this yet) bail for the other
+    /// variants.
+    fn get_diff_base_source(&self, file: &Path, source: DiffBaseSource) -> Result<Vec<u8>> {
+        match source {
+            DiffBaseSource::Head => self.get_diff_base(file),
+            DiffBaseSource::Index | DiffBaseSource::Auto => {
+                bail!("this provider does not support reading the diff base from the index")
+            }
+        }
+    }
+
+    /// Lists the files that differ between `rev_a` and `rev_b`, as
+    /// provider-specific revision strings (the same kind [`Self::merge_base`]
+    /// and [`Self::get_diff_base_rev`] accept). Unlike
+    /// [`Self::for_each_changed_file`], neither revision has to be the
+    /// working parent or the working directory.
+    fn changed_files_between(
+        &self,
+        _file: &Path,
+        _rev_a: &str,
+        _rev_b: &str,
+    ) -> Result<Vec<FileChange>> {
+        bail!("this provider does not support revision-to-revision diffs")
+    }
+
+    /// Reports the tracked mode (regular, executable, or symlink) of `file`.
+    /// Errors if `file` isn't tracked at all.
+    fn file_mode(&self, _file: &Path) -> Result<FileMode> {
+        bail!("this provider does not support reading a tracked file's mode")
+    }
+
+    /// Reports whether `a` and `b` are recorded as the same tracked content:
+    /// a hardlink, a not-yet-diverged duplicate, or one a rename/copy of the
+    /// other. Returns `Ok(false)` (not an error) when either path isn't
+    /// tracked, so callers can use this for "are these worth deduping"
+    /// checks without special-casing untracked inputs.
+    fn same_tracked_source(&self, _a: &Path, _b: &Path) -> Result<bool> {
+        bail!("this provider does not support tracked-source comparisons")
+    }
+
+    /// Combines [`Self::get_current_head_name`], [`Self::for_each_changed_file`]'s
+    /// aggregate counts, and whether `file` itself is one of those changed
+    /// files, for a statusline refresh that wants all three without tearing
+    /// between separate calls.
+    ///
+    /// The default implementation just calls the other methods in turn, so
+    /// it still re-opens the repository once per piece; providers that can
+    /// share one repository handle across all three (see `git`/`hg`) should
+    /// override this for the amortized cost the method exists for.
+    fn repo_snapshot(&self, file: &Path) -> Result<RepoSnapshot> {
+        let head_name = self.get_current_head_name(file)?;
+        let cwd = file.parent().context("file has no parent directory")?;
+
+        let mut counts = ChangeCounts::default();
+        let mut file_dirty = false;
+        self.for_each_changed_file(cwd, &StatusConfig::default(), &mut |change| {
+            if let Ok(change) = change {
+                if change.abs_path == file {
+                    file_dirty = true;
+                }
+                counts.record(change.kind);
+            }
+        })?;
+
+        Ok(RepoSnapshot {
+            head_name,
+            counts,
+            file_dirty,
+        })
+    }
+
+    /// Whether any file under `cwd` has unresolved merge-conflict markers.
+    /// `Ok(false)` when there's no merge in progress, same as an empty
+    /// [`ChangeKind::Conflict`] result from [`Self::for_each_changed_file`]
+    /// would imply.
+    ///
+    /// The default implementation walks every changed file via
+    /// [`Self::for_each_changed_file`] regardless of whether an earlier one
+    /// already answered the question; providers that can check "is a merge
+    /// even in progress" cheaply and stop as soon as the first conflict
+    /// turns up (see `git`/`hg`) should override this for the
+    /// short-circuiting the method exists for.
+    fn has_conflicts(&self, cwd: &Path) -> Result<bool> {
+        let mut found = false;
+        self.for_each_changed_file(cwd, &StatusConfig::default(), &mut |change| {
+            if let Ok(change) = change {
+                if change.kind == ChangeKind::Conflict {
+                    found = true;
+                }
+            }
+        })?;
+        Ok(found)
+    }
+
+    /// The first line of the working parent's commit message (its summary
+    /// line, by convention), truncated to `max_len` characters with a
+    /// trailing ellipsis if it was cut. `None` leaves it untruncated.
+    /// Errors (rather than panics) if the repository has no commits yet.
+    fn current_commit_summary(&self, _file: &Path, _max_len: Option<usize>) -> Result<String> {
+        bail!("this provider does not support commit summaries")
+    }
+
+    /// Attributes each line in `lines` (0-based, half-open, into the file as
+    /// it exists at the diff base) to the commit that last touched it,
+    /// walking first-parent history only - a real but approximate blame,
+    /// the same trade-off [`Self::merge_base`] already makes for
+    /// criss-cross histories. Errors clearly if `lines` extends past the
+    /// file's own length.
+    fn blame_range(&self, _file: &Path, _lines: Range<usize>) -> Result<Vec<BlameInfo>> {
+        bail!("this provider does not support blame")
+    }
+
+    /// Like [`Self::blame_range`], but for a scattered set of lines (e.g.
+    /// just the lines currently visible in a gutter) rather than a
+    /// contiguous range - the performance-minded sibling for a caller that
+    /// would otherwise call `blame_range` once per line. A real
+    /// implementation should annotate the file once and filter down to
+    /// `lines`, not repeat the annotate pass per line. Errors clearly if any
+    /// line in `lines` extends past the file's own length.
+    fn blame_lines(&self, _file: &Path, _lines: &[u32]) -> Result<HashMap<u32, BlameInfo>> {
+        bail!("this provider does not support blame")
+    }
+
+    /// Like [`Self::for_each_changed_file`], but bails with [`Cancelled`] if
+    /// `cancel` is tripped. The default implementation only checks once,
+    /// up front, since it has no visibility into the plain method's
+    /// internal loop; providers that walk a potentially-huge repo (see
+    /// `git`) should override this to check periodically during the walk
+    /// itself, the same way [`Self::for_each_changed_file_with_progress`]
+    /// reports progress periodically rather than just once.
+    fn for_each_changed_file_cancellable(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        f: &mut dyn FnMut(Result<FileChange>),
+        cancel: &CancelToken,
+    ) -> Result<()> {
+        if cancel.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        self.for_each_changed_file(cwd, config, f)
+    }
+
+    /// Like [`Self::blame_range`], but bails with [`Cancelled`] if `cancel`
+    /// is tripped before the blame completes.
+    fn blame_range_cancellable(
+        &self,
+        file: &Path,
+        lines: Range<usize>,
+        cancel: &CancelToken,
+    ) -> Result<Vec<BlameInfo>> {
+        if cancel.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        self.blame_range(file, lines)
+    }
+
+    /// Like [`Self::changed_files_between`], but bails with [`Cancelled`] if
+    /// `cancel` is tripped before the comparison completes.
+    fn changed_files_between_cancellable(
+        &self,
+        file: &Path,
+        rev_a: &str,
+        rev_b: &str,
+        cancel: &CancelToken,
+    ) -> Result<Vec<FileChange>> {
+        if cancel.is_cancelled() {
+            return Err(Cancelled.into());
+        }
+        self.changed_files_between(file, rev_a, rev_b)
+    }
+
+    /// The root directory of `file`'s working tree (hg's
+    /// `repo.working_directory_path()`, git's worktree root), for a
+    /// statusline that wants to show the repo's basename without
+    /// re-discovering the root itself.
+    fn working_directory(&self, _file: &Path) -> Result<PathBuf> {
+        bail!("this provider does not support locating a working directory")
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Shortens `text` to at most `max_len` characters, replacing the cut tail
+/// with a trailing `…` - shared by providers' `current_commit_summary` so a
+/// long first line doesn't blow out a statusline or picker row. `None` (or a
+/// `max_len` the text already fits within) returns `text` unchanged.
+pub(crate) fn truncate_with_ellipsis(text: &str, max_len: Option<usize>) -> String {
+    let Some(max_len) = max_len else {
+        return text.to_string();
+    };
+    if max_len == 0 {
+        return String::new();
+    }
+    if text.chars().count() <= max_len {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max_len - 1).collect();
+    format!("{truncated}…")
 }
 
 #[doc(hidden)]
@@ -33,6 +511,84 @@ fn get_diff_base(&self, _file: &Path) -> Result<Vec<u8>> {
     fn get_current_head_name(&self, _file: &Path) -> Result<Arc<ArcSwap<Box<str>>>> {
         bail!("helix was compiled without git support")
     }
+
+    fn is_file_dirty(&self, _file: &Path, _current_contents: &[u8]) -> Result<bool> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn for_each_changed_file(
+        &self,
+        _cwd: &Path,
+        _config: &StatusConfig,
+        _f: &mut dyn FnMut(Result<FileChange>),
+    ) -> Result<()> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn repo_capabilities(&self, _file: &Path) -> Result<RepoCaps> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn name(&self) -> &'static str {
+        "none"
+    }
+
+    fn merge_base(&self, _file: &Path, _rev_a: &str, _rev_b: &str) -> Result<String> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn get_diff_base_rev(&self, _file: &Path, _rev: &str) -> Result<Vec<u8>> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn get_diff_base_public(&self, _file: &Path) -> Result<Vec<u8>> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn head_state(&self, _file: &Path) -> Result<HeadState> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn changed_files_between(
+        &self,
+        _file: &Path,
+        _rev_a: &str,
+        _rev_b: &str,
+    ) -> Result<Vec<FileChange>> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn file_mode(&self, _file: &Path) -> Result<FileMode> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn same_tracked_source(&self, _a: &Path, _b: &Path) -> Result<bool> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn repo_snapshot(&self, _file: &Path) -> Result<RepoSnapshot> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn has_conflicts(&self, _cwd: &Path) -> Result<bool> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn current_commit_summary(&self, _file: &Path, _max_len: Option<usize>) -> Result<String> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn blame_range(&self, _file: &Path, _lines: Range<usize>) -> Result<Vec<BlameInfo>> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn blame_lines(&self, _file: &Path, _lines: &[u32]) -> Result<HashMap<u32, BlameInfo>> {
+        bail!("helix was compiled without git support")
+    }
+
+    fn working_directory(&self, _file: &Path) -> Result<PathBuf> {
+        bail!("helix was compiled without git support")
+    }
 }
 
 pub struct DiffProviderRegistry {
@@ -53,6 +609,18 @@ pub fn get_diff_base(&self, file: &Path) -> Option<Vec<u8>> {
             })
     }
 
+    /// Diffs `file`'s diff base against `current_contents` and flattens the
+    /// result into per-line added/removed/modified markers, for a gutter
+    /// that only needs line numbers rather than the full [`Hunk`] list
+    /// [`Self::get_diff_base`] would otherwise leave it to reconstruct. The
+    /// diff base is read as UTF-8 lossily; this crate has no encoding
+    /// support of its own to decode it more faithfully.
+    pub fn modified_lines(&self, file: &Path, current_contents: &Rope) -> Option<ChangedLines> {
+        let diff_base = self.get_diff_base(file)?;
+        let diff_base = Rope::from(String::from_utf8_lossy(&diff_base).into_owned());
+        Some(ChangedLines::for_document(&diff_base, current_contents))
+    }
+
     pub fn get_current_head_name(&self, file: &Path) -> Option<Arc<ArcSwap<Box<str>>>> {
         self.providers
             .iter()
@@ -65,14 +633,606 @@ pub fn get_current_head_name(&self, file: &Path) -> Option<Arc<ArcSwap<Box<str>>
                 }
             })
     }
+
+    /// Cheaply checks whether `current_contents` differs from the diff base,
+    /// without computing a full diff. Useful to skip re-diffing on every
+    /// keystroke when the buffer hasn't actually changed.
+    pub fn is_file_dirty(&self, file: &Path, current_contents: &[u8]) -> Option<bool> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.is_file_dirty(file, current_contents) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to check if {} is dirty", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Returns a named piece of review metadata (e.g. a Gerrit `Change-Id`)
+    /// attached to the working parent, as reported by the first provider
+    /// that recognizes `file`.
+    pub fn head_extra(&self, file: &Path, key: &str) -> Option<String> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.head_extra(file, key) {
+                Ok(res) => res,
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read head extra {key} for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Walks the tracked files below `cwd` with the first provider that
+    /// recognizes it, reporting every changed file to `f`. `config` selects
+    /// which extra kinds of file (unknown, ignored, clean) are also
+    /// reported, and can narrow the walk to a subdirectory.
+    pub fn for_each_changed_file(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        mut f: impl FnMut(Result<FileChange>),
+    ) -> Option<()> {
+        self.providers.iter().find_map(|provider| {
+            match provider.for_each_changed_file(cwd, config, &mut f) {
+                Ok(()) => Some(()),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to walk changed files in {}", cwd.display());
+                    None
+                }
+            }
+        })
+    }
+
+    /// Like [`Self::for_each_changed_file`], but also reports scan progress
+    /// via `progress`, for a UI spinner/counter during a walk over a huge
+    /// repo.
+    pub fn for_each_changed_file_with_progress(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        mut f: impl FnMut(Result<FileChange>),
+        mut progress: impl FnMut(usize),
+    ) -> Option<()> {
+        self.providers.iter().find_map(|provider| {
+            match provider.for_each_changed_file_with_progress(cwd, config, &mut f, &mut progress)
+            {
+                Ok(()) => Some(()),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to walk changed files in {}", cwd.display());
+                    None
+                }
+            }
+        })
+    }
+
+    /// Like [`Self::for_each_changed_file`], but bails out early with
+    /// [`Cancelled`] if `cancel` is tripped partway through the walk, for a
+    /// caller (e.g. a statusline refresh) that wants to drop a stale
+    /// request rather than wait for it to finish.
+    pub fn for_each_changed_file_cancellable(
+        &self,
+        cwd: &Path,
+        config: &StatusConfig,
+        mut f: impl FnMut(Result<FileChange>),
+        cancel: &CancelToken,
+    ) -> Option<()> {
+        self.providers.iter().find_map(|provider| {
+            match provider.for_each_changed_file_cancellable(cwd, config, &mut f, cancel) {
+                Ok(()) => Some(()),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to walk changed files in {}", cwd.display());
+                    None
+                }
+            }
+        })
+    }
+
+    /// Like [`Self::for_each_changed_file`], but collects the results,
+    /// dedupes and sorts them by `sort_by`, and truncates to `limit` if
+    /// given. Useful for a "jump to changed file" picker, where doing the
+    /// sort once here is cheaper than every consumer re-collecting.
+    pub fn changed_files_sorted(
+        &self,
+        cwd: &Path,
+        sort_by: ChangeSortKey,
+        limit: Option<usize>,
+    ) -> Option<Vec<FileChange>> {
+        let mut changes = Vec::new();
+        self.for_each_changed_file(cwd, &StatusConfig::default(), |change| {
+            if let Ok(change) = change {
+                changes.push(change);
+            }
+        })?;
+
+        match sort_by {
+            ChangeSortKey::Path => changes.sort_by(|a, b| a.path.cmp(&b.path)),
+            ChangeSortKey::KindThenPath => {
+                changes.sort_by(|a, b| a.kind.cmp(&b.kind).then_with(|| a.path.cmp(&b.path)))
+            }
+        }
+        changes.dedup();
+
+        if let Some(limit) = limit {
+            changes.truncate(limit);
+        }
+
+        Some(changes)
+    }
+
+    /// Reports whether `file`'s repository has a usable (writable) working
+    /// directory, as determined by the first provider that recognizes it.
+    /// Useful to grey out or reject write-ish actions up front rather than
+    /// letting them fail confusingly partway through.
+    pub fn repo_capabilities(&self, file: &Path) -> Option<RepoCaps> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.repo_capabilities(file) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read repo capabilities for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Returns the common-ancestor revision of `rev_a` and `rev_b`, as
+    /// reported by the first provider that recognizes `file`.
+    pub fn merge_base(&self, file: &Path, rev_a: &str, rev_b: &str) -> Option<String> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.merge_base(file, rev_a, rev_b) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to compute merge base for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Returns `file`'s contents at `rev`, as reported by the first
+    /// provider that recognizes it. `rev` is typically the result of
+    /// [`Self::merge_base`].
+    pub fn get_diff_base_rev(&self, file: &Path, rev: &str) -> Option<Vec<u8>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.get_diff_base_rev(file, rev) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read diff base at {rev} for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Returns `file`'s contents on `ref_name` (a branch, bookmark, or
+    /// tag), as reported by the first provider that recognizes it - powers
+    /// a "show this file as it is on main" buffer.
+    pub fn get_file_on_ref(&self, file: &Path, ref_name: &str) -> Option<Vec<u8>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.get_file_on_ref(file, ref_name) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read {ref_name}'s {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Returns `file`'s contents `n` first-parent ancestors back from the
+    /// working parent, as reported by the first provider that recognizes
+    /// it.
+    pub fn get_diff_base_ancestor(&self, file: &Path, n: u32) -> Option<Vec<u8>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.get_diff_base_ancestor(file, n) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!(
+                        "failed to read diff base {n} commits back for {}",
+                        file.display()
+                    );
+                    None
+                }
+            })
+    }
+
+    /// Returns `file`'s contents at the latest ancestor considered
+    /// "public", as reported by the first provider that recognizes it.
+    pub fn get_diff_base_public(&self, file: &Path) -> Option<Vec<u8>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.get_diff_base_public(file) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!(
+                        "failed to read public-ancestor diff base for {}",
+                        file.display()
+                    );
+                    None
+                }
+            })
+    }
+
+    /// Like [`Self::get_current_head_name`], but reports an unborn branch or
+    /// a detached checkout as such instead of an opaque display string, as
+    /// reported by the first provider that recognizes `file`.
+    pub fn head_state(&self, file: &Path) -> Option<HeadState> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.head_state(file) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read head state for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Like [`Self::get_diff_base`], but reads from `source` (the working
+    /// parent, the staging area, or whichever of the two has the file),
+    /// as reported by the first provider that recognizes `file`.
+    pub fn get_diff_base_source(&self, file: &Path, source: DiffBaseSource) -> Option<Vec<u8>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.get_diff_base_source(file, source) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read {source:?} diff base for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Lists the files that differ between `rev_a` and `rev_b`, as reported
+    /// by the first provider that recognizes `file`.
+    pub fn changed_files_between(
+        &self,
+        file: &Path,
+        rev_a: &str,
+        rev_b: &str,
+    ) -> Option<Vec<FileChange>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.changed_files_between(file, rev_a, rev_b) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!(
+                        "failed to diff {rev_a}..{rev_b} for {}",
+                        file.display()
+                    );
+                    None
+                }
+            })
+    }
+
+    /// Like [`Self::changed_files_between`], but bails out with
+    /// [`Cancelled`] if `cancel` is tripped before the comparison completes.
+    pub fn changed_files_between_cancellable(
+        &self,
+        file: &Path,
+        rev_a: &str,
+        rev_b: &str,
+        cancel: &CancelToken,
+    ) -> Option<Vec<FileChange>> {
+        self.providers.iter().find_map(|provider| {
+            match provider.changed_files_between_cancellable(file, rev_a, rev_b, cancel) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to diff {rev_a}..{rev_b} for {}", file.display());
+                    None
+                }
+            }
+        })
+    }
+
+    /// Reports the tracked mode of `file`, as reported by the first
+    /// provider that recognizes it.
+    pub fn file_mode(&self, file: &Path) -> Option<FileMode> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.file_mode(file) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read file mode for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Reports whether `a` and `b` are recorded as the same tracked content
+    /// (hardlink, duplicate, or rename/copy of each other), as reported by
+    /// the first provider that recognizes `a`.
+    pub fn same_tracked_source(&self, a: &Path, b: &Path) -> Option<bool> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.same_tracked_source(a, b) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!(
+                        "failed to compare tracked source of {} and {}",
+                        a.display(),
+                        b.display()
+                    );
+                    None
+                }
+            })
+    }
+
+    /// Returns the head name, aggregate change counts, and `file`'s dirty
+    /// flag together, as reported by the first provider that recognizes
+    /// `file`.
+    pub fn repo_snapshot(&self, file: &Path) -> Option<RepoSnapshot> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.repo_snapshot(file) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to snapshot repo state for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Whether any file under `cwd` has unresolved merge-conflict markers,
+    /// as reported by the first provider that recognizes `cwd`.
+    pub fn has_conflicts(&self, cwd: &Path) -> Option<bool> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.has_conflicts(cwd) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to check for conflicts in {}", cwd.display());
+                    None
+                }
+            })
+    }
+
+    /// Returns the working parent's commit summary (see
+    /// [`DiffProvider::current_commit_summary`]), as reported by the first
+    /// provider that recognizes `file`.
+    pub fn current_commit_summary(&self, file: &Path, max_len: Option<usize>) -> Option<String> {
+        self.providers.iter().find_map(|provider| {
+            match provider.current_commit_summary(file, max_len) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to read commit summary for {}", file.display());
+                    None
+                }
+            }
+        })
+    }
+
+    /// Attributes each line in `lines` (see
+    /// [`DiffProvider::blame_range`]) to the commit that last touched it, as
+    /// reported by the first provider that recognizes `file`.
+    pub fn blame_range(&self, file: &Path, lines: Range<usize>) -> Option<Vec<BlameInfo>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.blame_range(file, lines.clone()) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!(
+                        "failed to blame {} ({}..{})",
+                        file.display(),
+                        lines.start,
+                        lines.end
+                    );
+                    None
+                }
+            })
+    }
+
+    /// Attributes each line in `lines` (see [`DiffProvider::blame_lines`]) to
+    /// the commit that last touched it, as reported by the first provider
+    /// that recognizes `file`.
+    pub fn blame_lines(&self, file: &Path, lines: &[u32]) -> Option<HashMap<u32, BlameInfo>> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.blame_lines(file, lines) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to blame {} ({} lines)", file.display(), lines.len());
+                    None
+                }
+            })
+    }
+
+    /// Like [`Self::blame_range`], but bails out with [`Cancelled`] if
+    /// `cancel` is tripped before the blame completes.
+    pub fn blame_range_cancellable(
+        &self,
+        file: &Path,
+        lines: Range<usize>,
+        cancel: &CancelToken,
+    ) -> Option<Vec<BlameInfo>> {
+        self.providers.iter().find_map(|provider| {
+            match provider.blame_range_cancellable(file, lines.clone(), cancel) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!(
+                        "failed to blame {} ({}..{})",
+                        file.display(),
+                        lines.start,
+                        lines.end
+                    );
+                    None
+                }
+            }
+        })
+    }
+
+    /// The root of `file`'s working tree (see
+    /// [`DiffProvider::working_directory`]), as reported by the first
+    /// provider that recognizes it.
+    pub fn working_directory(&self, file: &Path) -> Option<PathBuf> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.working_directory(file) {
+                Ok(res) => Some(res),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to locate working directory for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Like [`Self::working_directory`], but also returns which provider
+    /// recognized `file` (its [`DiffProvider::name`], e.g. `"git"`), for
+    /// diagnostics like logging `"git at /path"` instead of just the bare
+    /// root. Centralizes the ancestor-walk each provider otherwise performs
+    /// independently (git's `.git` discovery, hg's `.hg` walk) behind one
+    /// call, trying every compiled-in provider in order and returning the
+    /// first to recognize `file`.
+    pub fn detect_provider(&self, file: &Path) -> Option<(&'static str, PathBuf)> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.working_directory(file) {
+                Ok(root) => Some((provider.name(), root)),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to detect a provider for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Reports the [`ProviderCapabilities`] of whichever provider recognizes
+    /// `file`, so a caller can feature-detect before offering an action
+    /// (e.g. disabling "stage hunk" for a provider with no index) instead
+    /// of invoking it and handling an "unsupported" error.
+    pub fn capabilities(&self, file: &Path) -> Option<ProviderCapabilities> {
+        self.providers
+            .iter()
+            .find_map(|provider| match provider.working_directory(file) {
+                Ok(_) => Some(provider.capabilities()),
+                Err(err) => {
+                    log::info!("{err:#?}");
+                    log::info!("failed to detect a provider for {}", file.display());
+                    None
+                }
+            })
+    }
+
+    /// Like [`Self::for_each_changed_file`], but only tallies how many files
+    /// fall into each change category instead of materializing every path.
+    /// Useful for a quick "N files changed" badge.
+    pub fn changed_file_counts(&self, cwd: &Path) -> Option<ChangeCounts> {
+        let mut counts = ChangeCounts::default();
+        self.for_each_changed_file(cwd, &StatusConfig::default(), |change| {
+            if let Ok(change) = change {
+                counts.record(change.kind);
+            }
+        })?;
+        Some(counts)
+    }
+
+    /// Like [`Self::for_each_changed_file`], but for a directory that may
+    /// contain several independent repositories side by side (a
+    /// dotfiles-style layout) rather than a single one: discovers every
+    /// repository at or under `dir`, walks each one's status with the first
+    /// provider that recognizes it, and reports every change tagged with
+    /// the repository it came from. A repository nested inside another is
+    /// not walked on its own: only the outer root is kept - this is about
+    /// independent, unlinked repos, not submodule-style nesting (see
+    /// [`discover_vcs_roots`]). Unlike [`Self::for_each_changed_file`], a
+    /// repo that no provider recognizes (or that fails outright) is logged
+    /// and skipped rather than stopping the whole walk.
+    pub fn for_each_changed_file_multiroot(
+        &self,
+        dir: &Path,
+        config: &StatusConfig,
+        mut f: impl FnMut(Result<MultiRootFileChange>),
+    ) {
+        for repo_root in discover_vcs_roots(dir) {
+            let path_prefix = repo_root.strip_prefix(dir).unwrap_or(&repo_root).to_path_buf();
+            let repo_root_for_closure = repo_root.clone();
+            let found = self.for_each_changed_file(&repo_root, config, |change| {
+                f(change.map(|mut change| {
+                    change.path = path_prefix.join(&change.path);
+                    MultiRootFileChange {
+                        repo_root: repo_root_for_closure.clone(),
+                        change,
+                    }
+                }));
+            });
+            if found.is_none() {
+                log::info!("no provider recognized repo root {}", repo_root.display());
+            }
+        }
+    }
+}
+
+/// Discovers every VCS repository at or under `dir` (a `.git` or `.hg`
+/// marker), for [`DiffProviderRegistry::for_each_changed_file_multiroot`]. A
+/// repository nested inside another is dropped in favor of the outer one:
+/// this is about finding independent, unlinked repos side by side, not
+/// submodule-style nesting, so there's no case where both roots should be
+/// walked separately over the same files.
+fn discover_vcs_roots(dir: &Path) -> Vec<PathBuf> {
+    fn is_vcs_root(dir: &Path) -> bool {
+        dir.join(".git").exists() || dir.join(".hg").is_dir()
+    }
+
+    let mut candidates = Vec::new();
+    if is_vcs_root(dir) {
+        candidates.push(dir.to_path_buf());
+    }
+    let mut builder = ignore::WalkBuilder::new(dir);
+    builder.hidden(false);
+    for entry in builder.build().flatten() {
+        if entry.path() == dir {
+            continue;
+        }
+        if entry.file_type().map_or(false, |ft| ft.is_dir()) && is_vcs_root(entry.path()) {
+            candidates.push(entry.path().to_path_buf());
+        }
+    }
+
+    candidates.sort();
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for candidate in candidates {
+        if !roots.iter().any(|root| candidate.starts_with(root)) {
+            roots.push(candidate);
+        }
+    }
+    roots
 }
 
 impl Default for DiffProviderRegistry {
     fn default() -> Self {
-        // currently only git is supported
         // TODO make this configurable when more providers are added
         let git: Box<dyn DiffProvider> = Box::new(Git);
-        let providers = vec![git];
+        #[allow(unused_mut)]
+        let mut providers = vec![git];
+        #[cfg(feature = "hg")]
+        providers.push(Box::new(HgProvider::default()) as Box<dyn DiffProvider>);
         DiffProviderRegistry { providers }
     }
 }
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A parsed `.mailmap` file (see `git-mailmap(5)`), mapping a commit's
+/// recorded `(name, email)` to the canonical one a project wants displayed
+/// instead, so e.g. `John D <jd@old.org>` and `John Doe <john@new.org>`
+/// collapse into a single author.
+///
+/// Nothing in this crate applies it: [`crate::BlameInfo`] carries a commit
+/// id and summary but no author name or email, and the blame methods never
+/// call [`Mailmap::canonicalize`]. There is no flag to turn it on or off.
+#[derive(Debug, Clone, Default)]
+pub struct Mailmap {
+    by_email: HashMap<String, (String, String)>,
+    by_name_email: HashMap<(String, String), (String, String)>,
+}
+
+impl Mailmap {
+    /// Parses a `.mailmap` file's contents. Unparseable lines are skipped
+    /// rather than failing the whole file, matching git's own leniency.
+    pub fn parse(contents: &str) -> Mailmap {
+        let mut mailmap = Mailmap::default();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            mailmap.parse_line(line);
+        }
+        mailmap
+    }
+
+    /// Reads and parses `dir`'s `.mailmap`, if present. A missing file is a
+    /// no-op (an empty mailmap) rather than an error.
+    pub fn load(dir: &Path) -> Mailmap {
+        std::fs::read_to_string(dir.join(".mailmap"))
+            .map(|contents| Mailmap::parse(&contents))
+            .unwrap_or_default()
+    }
+
+    fn parse_line(&mut self, line: &str) {
+        // Either of:
+        //   Proper Name <proper@email.xx> <commit@email.xx>
+        //   Proper Name <proper@email.xx> Commit Name <commit@email.xx>
+        let mut emails = Vec::new();
+        let mut rest = line;
+        while let Some(start) = rest.find('<') {
+            let Some(end) = rest[start..].find('>') else {
+                break;
+            };
+            emails.push(rest[start + 1..start + end].to_string());
+            rest = &rest[start + end + 1..];
+        }
+        let Some(first_close) = line.find('>') else {
+            return;
+        };
+        let Some(first_open) = line.find('<') else {
+            return;
+        };
+        if emails.is_empty() {
+            return;
+        }
+        let proper_name = line[..first_open].trim();
+        let proper_email = &emails[0];
+
+        if emails.len() == 1 {
+            self.by_email
+                .insert(proper_email.clone(), (proper_name.to_string(), proper_email.clone()));
+            return;
+        }
+
+        let commit_email = &emails[1];
+        let between = line[first_close + 1..]
+            .find('<')
+            .map(|next_open| line[first_close + 1..][..next_open].trim())
+            .unwrap_or("");
+        let commit_name = if between.is_empty() {
+            proper_name
+        } else {
+            between
+        };
+
+        self.by_name_email.insert(
+            (commit_name.to_string(), commit_email.clone()),
+            (proper_name.to_string(), proper_email.clone()),
+        );
+        self.by_email
+            .entry(commit_email.clone())
+            .or_insert_with(|| (proper_name.to_string(), proper_email.clone()));
+    }
+
+    /// Returns the canonical `(name, email)` for a commit's recorded
+    /// `(name, email)`, or the inputs unchanged if the mailmap has no entry
+    /// for them.
+    pub fn canonicalize(&self, name: &str, email: &str) -> (String, String) {
+        if let Some(canonical) = self.by_name_email.get(&(name.to_string(), email.to_string())) {
+            return canonical.clone();
+        }
+        if let Some(canonical) = self.by_email.get(email) {
+            return canonical.clone();
+        }
+        (name.to_string(), email.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn canonicalizes_by_email_alone() {
+        let mailmap = Mailmap::parse("Proper Name <proper@email.xx>\n");
+        assert_eq!(
+            mailmap.canonicalize("Commit Name", "proper@email.xx"),
+            ("Proper Name".to_string(), "proper@email.xx".to_string())
+        );
+    }
+
+    #[test]
+    fn canonicalizes_by_commit_email_remap() {
+        let mailmap = Mailmap::parse("Proper Name <proper@email.xx> <old@email.xx>\n");
+        assert_eq!(
+            mailmap.canonicalize("Anything", "old@email.xx"),
+            ("Proper Name".to_string(), "proper@email.xx".to_string())
+        );
+    }
+
+    #[test]
+    fn canonicalizes_by_commit_name_and_email() {
+        let mailmap =
+            Mailmap::parse("Proper Name <proper@email.xx> Commit Name <commit@email.xx>\n");
+        assert_eq!(
+            mailmap.canonicalize("Commit Name", "commit@email.xx"),
+            ("Proper Name".to_string(), "proper@email.xx".to_string())
+        );
+        // A different name at the same remapped email still resolves via
+        // the email-only fallback.
+        assert_eq!(
+            mailmap.canonicalize("Someone Else", "commit@email.xx"),
+            ("Proper Name".to_string(), "proper@email.xx".to_string())
+        );
+    }
+
+    #[test]
+    fn leaves_unknown_authors_unchanged() {
+        let mailmap = Mailmap::parse("Proper Name <proper@email.xx>\n");
+        assert_eq!(
+            mailmap.canonicalize("Someone", "someone@email.xx"),
+            ("Someone".to_string(), "someone@email.xx".to_string())
+        );
+    }
+
+    #[test]
+    fn missing_mailmap_is_a_no_op() {
+        let dir = tempfile::tempdir().unwrap();
+        let mailmap = Mailmap::load(dir.path());
+        assert_eq!(
+            mailmap.canonicalize("Someone", "someone@email.xx"),
+            ("Someone".to_string(), "someone@email.xx".to_string())
+        );
+    }
+}
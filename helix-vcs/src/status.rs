@@ -0,0 +1,284 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use arc_swap::ArcSwap;
+
+/// The kind of change detected for a single file during a status walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    /// Present on disk and in the diff base, but with different contents.
+    Modified,
+    /// Present on disk but not tracked in the diff base.
+    Added,
+    /// Tracked in the diff base but missing from disk.
+    Deleted,
+    /// Added under a new path whose content came from a path no longer
+    /// present in the diff base - [`FileChange::from_path`] carries that
+    /// old path. Reported instead of a separate [`ChangeKind::Added`] +
+    /// [`ChangeKind::Deleted`] pair, where the provider can tell the two
+    /// apart.
+    Renamed,
+    /// Like [`ChangeKind::Renamed`], but the source path is still present
+    /// in the diff base too (a copy, not a move). Only reported when
+    /// [`StatusConfig::list_copies`] is set - without it, a copy is
+    /// reported as a plain [`ChangeKind::Added`], the same as before this
+    /// variant existed.
+    Copied,
+    /// Has unresolved merge-conflict markers in its working-tree contents.
+    /// The hg provider also reports an ambiguous ("unsure") dirstate entry
+    /// this way unless [`StatusConfig::verify_unsure`] is set.
+    Conflict,
+    /// The VCS is configured to ignore this file. Only reported when
+    /// [`StatusConfig::list_ignored`] is set.
+    Ignored,
+    /// Tracked and unchanged. Only reported when [`StatusConfig::list_clean`]
+    /// is set.
+    Clean,
+}
+
+/// Configures what [`crate::DiffProvider::for_each_changed_file`] reports,
+/// beyond the modified/added/deleted/conflicted files it always includes.
+#[derive(Debug, Clone)]
+pub struct StatusConfig {
+    /// Include files present on disk but not tracked by the VCS, reported as
+    /// [`ChangeKind::Added`] - this crate doesn't distinguish a freshly
+    /// staged new file from one that's merely untracked.
+    pub list_unknown: bool,
+    /// Include files the VCS is configured to ignore (`.gitignore`,
+    /// `.hgignore`, ...), reported as [`ChangeKind::Ignored`].
+    pub list_ignored: bool,
+    /// Include tracked files with no changes, reported as
+    /// [`ChangeKind::Clean`].
+    pub list_clean: bool,
+    /// Attempt to detect copies, not just renames, where the provider
+    /// supports it.
+    pub list_copies: bool,
+    /// Report a permission-only (executable bit) change as
+    /// [`ChangeKind::Modified`] even when a file's contents are otherwise
+    /// identical.
+    pub check_exec: bool,
+    /// Restrict the walk to this path (relative to the repo root) instead of
+    /// scanning the whole repository.
+    pub subdir: Option<PathBuf>,
+    /// Resolve an ambiguous ("unsure") dirstate entry by hashing its
+    /// working-copy contents against the working parent's, rather than
+    /// conservatively reporting it as [`ChangeKind::Conflict`]. Only the hg
+    /// provider (`HgProvider`) has this concept; every other provider ignores this
+    /// flag. Off by default: the cheap assume-conflict path stays available
+    /// for callers that would rather not pay for a content comparison on
+    /// every ambiguous entry.
+    pub verify_unsure: bool,
+}
+
+impl Default for StatusConfig {
+    /// Matches the status walk's behavior before this struct existed:
+    /// untracked files are still reported (as [`ChangeKind::Added`]), but
+    /// nothing else extra is collected.
+    fn default() -> Self {
+        StatusConfig {
+            list_unknown: true,
+            list_ignored: false,
+            list_clean: false,
+            list_copies: false,
+            check_exec: false,
+            subdir: None,
+            verify_unsure: false,
+        }
+    }
+}
+
+/// A single file reported by [`crate::DiffProvider::for_each_changed_file`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    /// Path of the file, relative to the root that was walked. Suitable for
+    /// display (a changed-files picker, a status line, ...).
+    pub path: PathBuf,
+    /// Absolute path of the file, for callers that need to open or
+    /// otherwise operate on it directly without re-deriving it from `path`
+    /// and the walked root.
+    pub abs_path: PathBuf,
+    pub kind: ChangeKind,
+    /// For [`ChangeKind::Renamed`]/[`ChangeKind::Copied`], the path (same
+    /// root as `path`) the content came from. `None` for every other kind.
+    pub from_path: Option<PathBuf>,
+}
+
+/// One file change discovered by
+/// [`crate::DiffProviderRegistry::for_each_changed_file_multiroot`], tagging
+/// the underlying [`FileChange`] with the repository it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MultiRootFileChange {
+    /// Root of the repository `change` was reported by, as an absolute
+    /// path.
+    pub repo_root: PathBuf,
+    /// The change itself, with `path` re-rooted to be relative to the
+    /// directory `for_each_changed_file_multiroot` was called with (rather
+    /// than `repo_root`), so entries from different repos don't collide or
+    /// need re-qualifying by the caller.
+    pub change: FileChange,
+}
+
+/// How [`crate::DiffProviderRegistry::changed_files_sorted`] should order its
+/// results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeSortKey {
+    /// Sort by path alone.
+    Path,
+    /// Sort by change kind first, then by path within each kind.
+    KindThenPath,
+}
+
+/// Reported by [`crate::DiffProviderRegistry::repo_capabilities`] so
+/// consumers can disable write-ish actions (e.g. "stage this hunk") before
+/// attempting them, rather than failing confusingly partway through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepoCaps {
+    /// Whether the repository has a working directory at all (false for a
+    /// bare repository).
+    pub has_work_dir: bool,
+    /// Whether the working directory appears writable.
+    pub writable: bool,
+    /// Name of the provider that answered, e.g. `"git"`.
+    pub provider: &'static str,
+}
+
+/// Reported by [`crate::DiffProvider::capabilities`], a static bitset of
+/// optional operations a provider implements - unlike [`RepoCaps`], this
+/// doesn't depend on any particular repository's state, only on what the
+/// provider type itself has implemented. Lets a caller gray out an action
+/// (e.g. "stage hunk") up front instead of invoking it and handling an
+/// "unsupported" error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProviderCapabilities {
+    /// Supports [`crate::DiffProvider::blame_range`].
+    pub blame: bool,
+    /// Supports revision-range history lookups
+    /// ([`crate::DiffProvider::changed_files_between`]). Both the git and
+    /// hg providers set it, but only git implements
+    /// [`crate::DiffProvider::merge_base`]; hg's returns the trait
+    /// default's "not supported" error.
+    pub history: bool,
+    /// Supports reading a staged (index) diff base via
+    /// [`crate::DiffProvider::get_diff_base_source`] with
+    /// [`DiffBaseSource::Index`].
+    pub staged_diff: bool,
+}
+
+/// Reported by [`crate::DiffProvider::head_state`], distinguishing "the
+/// working parent has no name yet" from an actual failure to read it -
+/// [`crate::DiffProviderRegistry::get_current_head_name`] can't make that
+/// distinction, since it only ever returns a display string or bails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HeadState {
+    /// On a named branch with at least one commit.
+    Named(String),
+    /// On a named branch that has no commits yet (a freshly initialized
+    /// repository, or one just switched to an unborn branch) - there's
+    /// nothing wrong, there's just nothing to diff against yet.
+    Unborn(String),
+    /// Checked out at a specific revision rather than a branch, given as a
+    /// short, provider-specific revision string (e.g. an abbreviated git
+    /// hash).
+    Detached(String),
+}
+
+/// Selects what [`crate::DiffProvider::get_diff_base_source`] reads the diff
+/// base from, for providers (currently only git) that distinguish a staging
+/// area from the working parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffBaseSource {
+    /// The working parent (commit), same as [`crate::DiffProvider::get_diff_base`].
+    Head,
+    /// The staged (index) blob, so the diff shows only unstaged changes.
+    /// Errors if the file has nothing staged.
+    Index,
+    /// The staged blob if the file is staged, the working parent otherwise.
+    Auto,
+}
+
+/// Reported by [`crate::DiffProvider::file_mode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileMode {
+    Regular,
+    Executable,
+    Symlink,
+}
+
+/// One-shot combination of [`crate::DiffProvider::get_current_head_name`],
+/// [`crate::DiffProvider::for_each_changed_file`]'s aggregate counts, and
+/// whether the queried file is itself one of those changed files, returned
+/// by [`crate::DiffProvider::repo_snapshot`]. Meant for a statusline
+/// refresh: fetching the three separately can tear (e.g. show a just-
+/// switched head name against change counts computed before the switch),
+/// and re-opens the repository once per call.
+#[derive(Debug, Clone)]
+pub struct RepoSnapshot {
+    pub head_name: Arc<ArcSwap<Box<str>>>,
+    pub counts: ChangeCounts,
+    pub file_dirty: bool,
+}
+
+/// Totals produced by [`crate::DiffProviderRegistry::changed_file_counts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ChangeCounts {
+    pub modified: u32,
+    pub added: u32,
+    pub deleted: u32,
+    pub renamed: u32,
+    pub copied: u32,
+    pub conflict: u32,
+    pub ignored: u32,
+    pub clean: u32,
+}
+
+/// One line's attribution, returned by [`crate::DiffProvider::blame_range`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlameInfo {
+    /// 0-based index into the file as it exists at the diff base, matching
+    /// the `lines` range passed to `blame_range`.
+    pub line: usize,
+    /// Provider-specific id of the commit that last touched this line.
+    pub commit: String,
+    /// That commit's summary line (see
+    /// [`crate::DiffProvider::current_commit_summary`]), so a caller
+    /// doesn't need a second lookup just to show something.
+    pub summary: String,
+}
+
+impl ChangeCounts {
+    pub(crate) fn record(&mut self, kind: ChangeKind) {
+        match kind {
+            ChangeKind::Modified => self.modified += 1,
+            ChangeKind::Added => self.added += 1,
+            ChangeKind::Deleted => self.deleted += 1,
+            ChangeKind::Renamed => self.renamed += 1,
+            ChangeKind::Copied => self.copied += 1,
+            ChangeKind::Conflict => self.conflict += 1,
+            ChangeKind::Ignored => self.ignored += 1,
+            ChangeKind::Clean => self.clean += 1,
+        }
+    }
+
+    /// Total *changed* files - deliberately excludes [`Self::ignored`] and
+    /// [`Self::clean`], since neither represents a change worth badging.
+    pub fn total(&self) -> u32 {
+        self.modified + self.added + self.deleted + self.renamed + self.copied + self.conflict
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn total_counts_a_tracked_rename_and_copy() {
+        let mut counts = ChangeCounts::default();
+        counts.record(ChangeKind::Modified);
+        counts.record(ChangeKind::Renamed);
+        counts.record(ChangeKind::Copied);
+
+        assert_eq!(counts.renamed, 1);
+        assert_eq!(counts.copied, 1);
+        assert_eq!(counts.total(), 3);
+    }
+}